@@ -1,8 +1,72 @@
 #version 330 core
 
-uniform vec4 u_color;
+in vec4 v_color;
+in vec3 v_normal;
+in vec3 v_world_pos;
+in vec4 v_light_space_pos;
+
+// directional light, see src/render/light.rs
+uniform vec3 u_light_dir;
+uniform vec3 u_light_color;
+uniform float u_ambient;
+uniform float u_specular;
+uniform float u_shininess;
+
+uniform sampler2D u_shadow_map;
+
+uniform vec3 u_view_pos;
+
+out vec4 frag_color;
+
+// how much of the light reaches the fragment, averaged over the 3x3 texels
+// around it to soften the edges
+float light_amount(vec3 normal, vec3 to_light)
+{
+    vec3 coords = v_light_space_pos.xyz / v_light_space_pos.w * 0.5 + 0.5;
+
+    if (coords.z > 1.0) {
+        return 1.0;
+    }
+
+    // faces at a grazing angle to the light need more bias against acne
+    float bias = max(0.002 * (1.0 - dot(normal, to_light)), 0.0005);
+
+    vec2 texel = 1.0 / vec2(textureSize(u_shadow_map, 0));
+
+    float lit = 0.0;
+
+    for (int x = -1; x <= 1; ++x) {
+        for (int y = -1; y <= 1; ++y) {
+            float depth = texture(u_shadow_map, coords.xy + vec2(x, y) * texel).r;
+
+            lit += coords.z - bias > depth ? 0.0 : 1.0;
+        }
+    }
+
+    return lit / 9.0;
+}
 
 void main()
 {
-    gl_FragColor = vec4(u_color);
+    vec3 normal = normalize(v_normal);
+    vec3 to_light = normalize(-u_light_dir);
+    vec3 to_view = normalize(u_view_pos - v_world_pos);
+
+    float diffuse = max(dot(normal, to_light), 0.0);
+
+    // blinn-phong, faces turned away from the light get no highlight
+    float specular = 0.0;
+
+    if (diffuse > 0.0) {
+        vec3 halfway = normalize(to_light + to_view);
+
+        specular = pow(max(dot(normal, halfway), 0.0), u_shininess) * u_specular;
+    }
+
+    float lit = light_amount(normal, to_light);
+
+    vec3 color = v_color.rgb * (u_ambient + lit * diffuse * u_light_color)
+        + lit * specular * u_light_color;
+
+    frag_color = vec4(color, v_color.a);
 }
@@ -0,0 +1,12 @@
+#version 330 core
+
+in vec2 v_uv;
+
+uniform sampler2D u_text;
+
+out vec4 frag_color;
+
+void main()
+{
+    frag_color = texture(u_text, v_uv);
+}
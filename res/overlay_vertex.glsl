@@ -0,0 +1,16 @@
+#version 330 core
+
+layout (location = 0) in vec2 a_corner;
+
+// left, bottom, width and height in normalized device coordinates
+uniform vec4 u_rect;
+
+out vec2 v_uv;
+
+void main()
+{
+    gl_Position = vec4(u_rect.xy + a_corner * u_rect.zw, 0.0, 1.0);
+
+    // the text image starts from its top row
+    v_uv = vec2(a_corner.x, 1.0 - a_corner.y);
+}
@@ -0,0 +1,26 @@
+#version 330 core
+
+layout (location = 0) in vec3 a_pos;
+
+// same instances as vertex.glsl
+layout (location = 1) in vec3 a_block;
+
+uniform mat4 u_light_space;
+
+uniform float u_column_offset;
+uniform float u_sector_angle;
+
+void main()
+{
+    float angle = (a_block.x - u_column_offset) * u_sector_angle - 3.14159265 * 0.5;
+
+    float c = cos(angle);
+    float s = sin(angle);
+
+    vec3 pos = vec3(a_pos.x, a_pos.y + a_block.y, a_pos.z);
+
+    // rotation around the y axis, same as glm::rotate
+    pos = vec3(c * pos.x + s * pos.z, pos.y, c * pos.z - s * pos.x) * a_block.z;
+
+    gl_Position = u_light_space * vec4(pos, 1.0f);
+}
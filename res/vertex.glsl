@@ -1,10 +1,43 @@
 #version 330 core
 
 layout (location = 0) in vec3 a_pos;
+layout (location = 3) in vec3 a_normal;
 
-uniform mat4 u_final_mat;
+// column, height in sector heights and scale of the block
+layout (location = 1) in vec3 a_block;
+layout (location = 2) in vec4 a_color;
+
+uniform mat4 u_view_projection;
+uniform mat4 u_light_space;
+
+uniform float u_column_offset;
+uniform float u_sector_angle;
+
+out vec4 v_color;
+out vec3 v_normal;
+out vec3 v_world_pos;
+out vec4 v_light_space_pos;
+
+// rotation around the y axis, same as glm::rotate
+vec3 rotate_y(vec3 v, float c, float s)
+{
+    return vec3(c * v.x + s * v.z, v.y, c * v.z - s * v.x);
+}
 
 void main()
 {
-    gl_Position = u_final_mat * vec4(a_pos, 1.0f);
+    float angle = (a_block.x - u_column_offset) * u_sector_angle - 3.14159265 * 0.5;
+
+    float c = cos(angle);
+    float s = sin(angle);
+
+    vec3 pos = rotate_y(vec3(a_pos.x, a_pos.y + a_block.y, a_pos.z), c, s) * a_block.z;
+
+    gl_Position = u_view_projection * vec4(pos, 1.0f);
+
+    // the scale is uniform so the normal only needs the rotation
+    v_normal = rotate_y(a_normal, c, s);
+    v_world_pos = pos;
+    v_light_space_pos = u_light_space * vec4(pos, 1.0f);
+    v_color = a_color;
 }
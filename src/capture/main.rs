@@ -0,0 +1,45 @@
+use tetris_3d::render::{self, CameraSettings};
+use tetris_3d::GameLogic;
+
+use std::process::ExitCode;
+
+const SIZE: (u32, u32) = (640, 640);
+
+/// tetris-3d-capture <png> [seed], saves the first frame of a game drawn on
+/// the cpu, for machines without a display or OpenGL.
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let file_path = match args.first() {
+        Some(file_path) => file_path,
+        None => {
+            eprintln!("usage: tetris-3d-capture <png> [seed]");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let seed = match args.get(1).map(|seed| seed.parse()) {
+        None => 0,
+        Some(Ok(seed)) => seed,
+        Some(Err(_)) => {
+            eprintln!("invalid seed: {}", args[1]);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let settings = CameraSettings::load("./res/camera.toml").unwrap_or_else(|error| {
+        println!("[WARNING]: {}, using the default camera settings", error);
+        CameraSettings::default()
+    });
+
+    let mut game = GameLogic::with_seed(15, 20, seed);
+
+    game.start();
+
+    if let Err(error) = render::capture(&game, settings, SIZE, 0.0, file_path) {
+        eprintln!("cannot save the frame: {}", error);
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
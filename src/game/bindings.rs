@@ -0,0 +1,181 @@
+use super::config::{self, ConfigError};
+use super::input::{Command, Key};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+/// Everything a key can be bound to, the game actions become a `Command`
+/// and the rest is left to the front-end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    HardDrop,
+    RotateCw,
+    RotateCcw,
+    Rotate180,
+    Hold,
+    Pause,
+    Confirm,
+    Quit,
+
+    CameraForward,
+    CameraBack,
+    CameraLeft,
+    CameraRight,
+    /// Frees the mouse from mouse look.
+    ToggleCursor,
+    /// Switches between orbiting the cylinder and flying around.
+    ToggleCamera,
+    /// Flies to the next camera preset.
+    NextCamera,
+    /// Goes back to orbiting the piece.
+    ResetCamera,
+    /// Switches the window between fullscreen and windowed.
+    ToggleFullscreen,
+}
+
+/// Actions with their names in the bindings file and the keys they start with.
+const DEFAULTS: [(Action, &str, &[Key]); 20] = [
+    (Action::MoveLeft, "move_left", &[Key::Left]),
+    (Action::MoveRight, "move_right", &[Key::Right]),
+    (Action::SoftDrop, "soft_drop", &[Key::Down]),
+    (Action::HardDrop, "hard_drop", &[Key::Space]),
+    (Action::RotateCw, "rotate_cw", &[Key::Up, Key::Char('x')]),
+    (Action::RotateCcw, "rotate_ccw", &[Key::Char('z')]),
+    (Action::Rotate180, "rotate_180", &[Key::Char('v')]),
+    (Action::Hold, "hold", &[Key::Char('c'), Key::LeftShift]),
+    (Action::Pause, "pause", &[Key::Char('p')]),
+    (Action::Confirm, "confirm", &[Key::Enter]),
+    (Action::Quit, "quit", &[Key::Escape]),
+    (Action::CameraForward, "camera_forward", &[Key::Char('w')]),
+    (Action::CameraBack, "camera_back", &[Key::Char('s')]),
+    (Action::CameraLeft, "camera_left", &[Key::Char('a')]),
+    (Action::CameraRight, "camera_right", &[Key::Char('d')]),
+    (Action::ToggleCursor, "toggle_cursor", &[Key::F1]),
+    (Action::ToggleCamera, "toggle_camera", &[Key::Char('o')]),
+    (Action::NextCamera, "next_camera", &[Key::Char('n')]),
+    (Action::ResetCamera, "reset_camera", &[Key::Char('r')]),
+    (Action::ToggleFullscreen, "toggle_fullscreen", &[Key::F11]),
+];
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        DEFAULTS
+            .iter()
+            .find(|(action, ..)| *action == self)
+            .map(|(_, name, _)| *name)
+            .expect("every action has a name")
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        DEFAULTS
+            .iter()
+            .find(|(_, it, _)| *it == name)
+            .map(|(action, ..)| *action)
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Which keys trigger which action. A key belongs to at most one action,
+/// an action can have any number of keys, including none.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBindings {
+    keys: BTreeMap<Action, Vec<Key>>,
+    actions: HashMap<Key, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let keys = DEFAULTS
+            .iter()
+            .map(|(action, _, keys)| (*action, keys.to_vec()))
+            .collect();
+
+        KeyBindings::from_keys(keys).expect("the default bindings don't conflict")
+    }
+}
+
+impl KeyBindings {
+    /// Reads the bindings from a toml file, a missing file gives the defaults.
+    pub fn load(file_path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        config::load_table(file_path)?.map_or(Ok(Self::default()), Self::from_table)
+    }
+
+    /// Actions are set with the names of their keys, e.g.
+    /// `hold = ["c", "left_shift"]` or `pause = "p"`. The actions left out
+    /// keep their default keys, an empty list unbinds one.
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        Self::from_table(config::parse_table(source)?)
+    }
+
+    fn from_table(table: toml::Table) -> Result<Self, ConfigError> {
+        let mut keys = KeyBindings::default().keys;
+
+        for (name, value) in table {
+            let action = Action::from_name(&name).ok_or(ConfigError::UnknownAction(name))?;
+
+            let names = match value {
+                toml::Value::String(name) => vec![toml::Value::String(name)],
+                toml::Value::Array(names) => names,
+                _ => return Err(ConfigError::NotAKeyList(action)),
+            };
+
+            let action_keys = names
+                .iter()
+                .map(|name| {
+                    let name = name.as_str().ok_or(ConfigError::NotAKeyList(action))?;
+
+                    name.parse().map_err(|_| ConfigError::UnknownKey {
+                        action,
+                        key: name.to_string(),
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+
+            keys.insert(action, action_keys);
+        }
+
+        Self::from_keys(keys)
+    }
+
+    fn from_keys(keys: BTreeMap<Action, Vec<Key>>) -> Result<Self, ConfigError> {
+        let mut actions = HashMap::new();
+
+        for (&action, action_keys) in &keys {
+            for key in action_keys.iter().map(|key| key.normalized()) {
+                match actions.insert(key, action) {
+                    Some(other) if other != action => {
+                        return Err(ConfigError::Conflict {
+                            key,
+                            actions: (other, action),
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(KeyBindings { keys, actions })
+    }
+
+    pub fn action_for(&self, key: Key) -> Option<Action> {
+        self.actions.get(&key.normalized()).copied()
+    }
+
+    pub fn keys_for(&self, action: Action) -> &[Key] {
+        self.keys.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// The command for a pressed `key`, see `Command::for_action`.
+    pub fn command_for(&self, key: Key) -> Option<Command> {
+        self.action_for(key).and_then(Command::for_action)
+    }
+}
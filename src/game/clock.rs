@@ -0,0 +1,66 @@
+use std::time::{Duration, Instant};
+
+/// Time source for `GameLogic::update`, every `tick` returns the time
+/// passed since the previous one.
+pub trait Clock {
+    fn tick(&mut self) -> Duration;
+}
+
+/// Wall clock, used by the interactive front-ends.
+pub struct SystemClock {
+    last_tick: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock {
+            last_tick: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn tick(&mut self) -> Duration {
+        let now = Instant::now();
+        let dt = now - self.last_tick;
+
+        self.last_tick = now;
+
+        dt
+    }
+}
+
+/// Clock that advances by a fixed step on every tick, for tests, replays
+/// and fast-forwarded simulations.
+pub struct ManualClock {
+    pub step: Duration,
+    elapsed: Duration,
+}
+
+impl ManualClock {
+    pub fn new(step: Duration) -> Self {
+        ManualClock {
+            step,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Total time handed out so far.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+impl Clock for ManualClock {
+    fn tick(&mut self) -> Duration {
+        self.elapsed += self.step;
+
+        self.step
+    }
+}
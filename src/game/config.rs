@@ -0,0 +1,66 @@
+use super::bindings::Action;
+use super::input::Key;
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// What can go wrong reading one of the toml files of the front-ends.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    UnknownAction(String),
+    UnknownKey {
+        action: Action,
+        key: String,
+    },
+    /// The value of an action is neither a key name nor a list of them.
+    NotAKeyList(Action),
+    /// One key bound to two actions.
+    Conflict {
+        key: Key,
+        actions: (Action, Action),
+    },
+    UnknownSetting(String),
+    /// A setting with the wrong type or out of its range.
+    InvalidValue(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "cannot access the file: {}", error),
+            ConfigError::Parse(error) => write!(f, "invalid toml: {}", error),
+            ConfigError::UnknownAction(name) => write!(f, "unknown action \"{}\"", name),
+            ConfigError::UnknownKey { action, key } => {
+                write!(f, "unknown key \"{}\" for {}", key, action)
+            }
+            ConfigError::NotAKeyList(action) => {
+                write!(f, "{} has to be a key or a list of keys", action)
+            }
+            ConfigError::Conflict { key, actions } => write!(
+                f,
+                "\"{}\" is bound to both {} and {}",
+                key, actions.0, actions.1
+            ),
+            ConfigError::UnknownSetting(name) => write!(f, "unknown setting \"{}\"", name),
+            ConfigError::InvalidValue(name) => write!(f, "invalid value for {}", name),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// The table in `file_path`, or `None` if there is no such file.
+pub(crate) fn load_table(file_path: impl AsRef<Path>) -> Result<Option<toml::Table>, ConfigError> {
+    match std::fs::read_to_string(file_path) {
+        Ok(source) => parse_table(&source).map(Some),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(ConfigError::Io(error)),
+    }
+}
+
+pub(crate) fn parse_table(source: &str) -> Result<toml::Table, ConfigError> {
+    source.parse().map_err(ConfigError::Parse)
+}
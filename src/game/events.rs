@@ -0,0 +1,38 @@
+use super::rotation::Rotation;
+use super::scoring::Spin;
+
+/// Things that happened inside `GameLogic`, collected until the next
+/// `GameLogic::drain_events`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    PieceSpawned {
+        piece_type: usize,
+    },
+    /// The piece was shifted sideways by `movement` columns.
+    Moved {
+        movement: i32,
+    },
+    Rotated {
+        rotation: Rotation,
+        kick: usize,
+    },
+    /// The current piece went to the hold slot.
+    Held {
+        piece_type: usize,
+    },
+    HardDropped {
+        distance: u32,
+    },
+    Locked {
+        spin: Spin,
+        perfect_clear: bool,
+    },
+    /// Sent on lock, the rows stay on the grid while they flash.
+    LinesCleared {
+        rows: Vec<usize>,
+    },
+    LevelUp {
+        level: u32,
+    },
+    GameOver,
+}
@@ -0,0 +1,995 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+
+use super::events::GameEvent;
+use super::piece_generator::{BagGenerator, PieceGenerator};
+use super::rotation::{self, Rotation};
+use super::scoring::{Spin, Stats};
+
+use nalgebra_glm as glm;
+
+pub type Vec2 = glm::TVec2<i32>;
+pub type Mat2 = glm::TMat2<i32>;
+pub type SeedT = u64;
+
+pub const EMPTY_BLOCK: SeedT = u64::MAX;
+pub const I_TETROMINO: usize = 0; // I-block index
+pub const J_TETROMINO: usize = 1; // J-block index
+pub const L_TETROMINO: usize = 2; // L-block index
+pub const O_TETROMINO: usize = 3; // O-block index
+pub const S_TETROMINO: usize = 4; // S-block index
+pub const T_TETROMINO: usize = 5; // T-block index
+pub const Z_TETROMINO: usize = 6; // Z-block index
+/// How fast a moved piece slides to its column, the gap shrinks to 1/e in
+/// 1/`BLOCKS_MOVE_RATE` seconds.
+const BLOCKS_MOVE_RATE: f32 = 3.0;
+const SPAWN_POS_Y: i32 = 2;
+const LINE_CLEAR_TIME: Duration = Duration::from_millis(300);
+const DEFAULT_LOCK_DELAY: Duration = Duration::from_millis(500);
+const DEFAULT_SOFT_DROP_FACTOR: u32 = 4;
+/// Moves and rotations on the ground that restart the lock delay, reaching a
+/// new lowest row gives them back.
+const MAX_LOCK_RESETS: u32 = 15;
+/// Total time a piece can spend on the ground, whatever the player does.
+const MAX_LOCK_TIME: Duration = Duration::from_secs(5);
+/// Where the held piece floats, left of the front column and above the grid.
+const HOLD_DRAW_POS: (f32, f32) = (-4.0, -4.0);
+/// Events kept until `drain_events`, the oldest ones are dropped past it so
+/// a front-end that never drains doesn't grow the queue forever.
+pub const MAX_QUEUED_EVENTS: usize = 256;
+pub const MAX_PREVIEW_LEN: usize = 6;
+
+pub const TETROMINO_COORDS: [[Vec2; 3]; 7] = [
+    [Vec2::new(-1, 0), Vec2::new(1, 0), Vec2::new(2, 0)], // I-tetromino
+    [Vec2::new(-1, -1), Vec2::new(-1, 0), Vec2::new(1, 0)], // J-tetromino
+    [Vec2::new(-1, 0), Vec2::new(1, 0), Vec2::new(1, -1)], // L-tetromino
+    [Vec2::new(1, 0), Vec2::new(0, 1), Vec2::new(1, 1)],  // O-tetromino
+    [Vec2::new(-1, 0), Vec2::new(0, -1), Vec2::new(1, -1)], // S-tetromino
+    [Vec2::new(-1, 0), Vec2::new(0, -1), Vec2::new(1, 0)], // T-tetromino
+    [Vec2::new(-1, -1), Vec2::new(0, -1), Vec2::new(1, 0)], // Z-tetromino
+];
+
+pub struct DroppingPiece {
+    pub pos: Vec2,
+    pub drop_pos: i32,
+    pub piece_type: usize,
+    /// Rotation state, 0 is the spawn state and the rest go clockwise.
+    pub rotation: usize,
+
+    /// Trails `pos` to animate the falls and the turns of the cylinder.
+    pub draw_pos: glm::Vec2,
+}
+
+impl DroppingPiece {
+    pub fn new(pos: Vec2, piece_type: usize) -> Self {
+        DroppingPiece {
+            pos,
+            drop_pos: 0,
+            draw_pos: pos.cast::<f32>(),
+            piece_type,
+            rotation: 0,
+        }
+    }
+
+    pub fn renew(&mut self, pos_y: i32, piece_type: usize) {
+        self.pos.y = pos_y;
+        self.draw_pos.y = pos_y as f32;
+
+        self.drop_pos = 0;
+
+        self.piece_type = piece_type;
+        self.rotation = 0;
+    }
+
+    pub fn get_coord_at(&self, index: usize) -> Vec2 {
+        rotation::rotation_matrix(self.rotation) * TETROMINO_COORDS[self.piece_type][index]
+    }
+
+    pub fn get_pos_at(&self, index: usize) -> Vec2 {
+        if index > 0 {
+            self.pos + self.get_coord_at(index - 1)
+        } else {
+            self.pos
+        }
+    }
+
+    pub fn draw_with(&self, func: impl Fn(glm::Vec2)) {
+        let vec = glm::vec2(self.pos.x as f32, self.draw_pos.y);
+
+        func(vec);
+
+        for i in 0..3 {
+            func(vec + self.get_coord_at(i).cast::<f32>());
+        }
+    }
+
+    pub fn draw_dropped_with(&self, func: impl Fn(glm::Vec2)) {
+        let vec = glm::vec2(self.pos.x as f32, self.drop_pos as f32);
+
+        func(vec);
+
+        for i in 0..3 {
+            func(vec + self.get_coord_at(i).cast::<f32>());
+        }
+    }
+
+    pub fn rotate(&mut self, rotation: Rotation) {
+        self.rotation = rotation.apply(self.rotation);
+    }
+}
+
+/// The row the pivot of a new piece starts on. J, L, S, T and Z have a cell
+/// above their pivot, they start one lower so every piece has its top row on
+/// the spawn row.
+fn spawn_row(piece_type: usize) -> i32 {
+    let top = TETROMINO_COORDS[piece_type]
+        .iter()
+        .map(|coord| coord.y)
+        .fold(0, i32::min);
+
+    SPAWN_POS_Y - top
+}
+
+pub fn generate_color(seed: SeedT, alpha: f32) -> Option<glm::Vec4> {
+    match seed {
+        EMPTY_BLOCK => None,
+        _ => {
+            let mix_color: glm::Vec3 = glm::vec3(0.4, 0.2, 0.8);
+
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            let color =
+                (glm::vec3(rng.gen::<f32>(), rng.gen::<f32>(), rng.gen::<f32>()) + mix_color) / 2.0;
+
+            Some(glm::vec4(color.x, color.y, color.z, alpha))
+        }
+    }
+}
+
+/// Last thing that successfully moved the current piece, T-spins need it to
+/// be a rotation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LastMove {
+    None,
+    Shift,
+    Drop,
+    /// `kick` is the index of the SRS test that was used.
+    Rotation {
+        kick: usize,
+    },
+}
+
+/// What happened when the last piece locked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockResult {
+    /// Cleared rows from top to bottom.
+    pub rows: Vec<usize>,
+    pub spin: Spin,
+    /// The clear left the grid completely empty.
+    pub perfect_clear: bool,
+}
+
+impl LockResult {
+    fn new() -> Self {
+        LockResult {
+            rows: Vec::new(),
+            spin: Spin::None,
+            perfect_clear: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameState {
+    /// Waiting for `start`, nothing moves.
+    Ready,
+    Playing,
+    Paused,
+    /// Full rows are still on the grid and flashing, the next piece spawns
+    /// once they are removed.
+    LineClearAnimating,
+    /// The stack reached the spawn point, only `restart` does anything.
+    GameOver,
+}
+
+/// Rules of a single game. Every change is also queued as a `GameEvent`,
+/// up to `MAX_QUEUED_EVENTS` of them, for `drain_events`.
+pub struct GameLogic {
+    pub grid_width: usize,
+    pub grid_height: usize,
+
+    grid: Vec<SeedT>,
+    grid_revision: u64,
+
+    state: GameState,
+
+    drop_timer: Duration,
+    soft_drop: bool,
+    soft_drop_factor: u32,
+
+    lock_delay: Duration,
+    lock_timer: Duration,
+    ground_timer: Duration,
+    lock_resets: u32,
+    lowest_row: i32,
+
+    line_clear_timer: Duration,
+
+    // every dealt piece gets the next seed, its color stays the same from
+    // the preview or the hold slot to the grid
+    current_seed: SeedT,
+    next_seed: SeedT,
+    current_piece: DroppingPiece,
+
+    last_move: LastMove,
+    last_lock: LockResult,
+
+    events: VecDeque<GameEvent>,
+
+    // piece types with their seeds
+    held_piece: Option<(usize, SeedT)>,
+    hold_used: bool,
+
+    // always MAX_PREVIEW_LEN pieces, so changing preview_len never changes
+    // the piece sequence
+    preview: VecDeque<(usize, SeedT)>,
+    preview_len: usize,
+
+    stats: Stats,
+
+    generator: Box<dyn PieceGenerator>,
+}
+
+impl GameLogic {
+    /// Creates a game with a 7-bag randomizer seeded from the OS.
+    pub fn new(grid_width: usize, grid_height: usize) -> Self {
+        Self::with_seed(grid_width, grid_height, thread_rng().gen())
+    }
+
+    /// Creates a game with a 7-bag randomizer, games with the same seed get
+    /// the same piece sequence.
+    pub fn with_seed(grid_width: usize, grid_height: usize, seed: u64) -> Self {
+        Self::with_generator(grid_width, grid_height, Box::new(BagGenerator::new(seed)))
+    }
+
+    pub fn with_generator(
+        grid_width: usize,
+        grid_height: usize,
+        mut generator: Box<dyn PieceGenerator>,
+    ) -> Self {
+        let mut grid = Vec::<SeedT>::new();
+
+        grid.resize(grid_width * grid_height, EMPTY_BLOCK);
+
+        let current_piece = generator.next_piece();
+
+        let preview = (1..=MAX_PREVIEW_LEN as SeedT)
+            .map(|seed| (generator.next_piece(), seed))
+            .collect();
+
+        let mut game = GameLogic {
+            grid_width,
+            grid_height,
+
+            grid,
+            grid_revision: 0,
+
+            state: GameState::Ready,
+
+            current_piece: DroppingPiece::new(
+                glm::vec2(grid_width as i32 / 2, spawn_row(current_piece)),
+                current_piece,
+            ),
+
+            drop_timer: Duration::ZERO,
+            soft_drop: false,
+            soft_drop_factor: DEFAULT_SOFT_DROP_FACTOR,
+
+            lock_delay: DEFAULT_LOCK_DELAY,
+            lock_timer: Duration::ZERO,
+            ground_timer: Duration::ZERO,
+            lock_resets: 0,
+            lowest_row: -2,
+
+            line_clear_timer: Duration::ZERO,
+
+            current_seed: 0,
+            next_seed: MAX_PREVIEW_LEN as SeedT + 1,
+
+            last_move: LastMove::None,
+            last_lock: LockResult::new(),
+
+            events: VecDeque::with_capacity(MAX_QUEUED_EVENTS),
+
+            held_piece: None,
+            hold_used: false,
+
+            preview,
+            preview_len: 5,
+
+            stats: Stats::new(),
+
+            generator,
+        };
+
+        game.update_dropped_pos();
+
+        game
+    }
+
+    /// Advances the game by `dt`. The game never reads the wall clock itself,
+    /// so feeding the same sequence of `dt`s always gives the same game.
+    pub fn update(&mut self, dt: Duration) {
+        match self.state {
+            GameState::Playing => self.update_piece(dt),
+            GameState::LineClearAnimating => {
+                self.line_clear_timer += dt;
+
+                if self.line_clear_timer >= LINE_CLEAR_TIME {
+                    self.remove_cleared_rows();
+                    self.spawn_next_piece();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn update_piece(&mut self, dt: Duration) {
+        let diff = self.current_piece.pos.x as f32 - self.current_piece.draw_pos.x;
+        let t = 1.0 - (-BLOCKS_MOVE_RATE * dt.as_secs_f32()).exp();
+
+        self.current_piece.draw_pos.x += diff * t;
+
+        let mut time = dt;
+
+        // split dt between falling and the lock delay, so a big dt gives the
+        // same result as many small ones
+        while self.state == GameState::Playing && time > Duration::ZERO {
+            if self.is_piece_grounded() {
+                self.drop_timer = Duration::ZERO;
+
+                let left = (self.lock_delay - self.lock_timer.min(self.lock_delay))
+                    .min(MAX_LOCK_TIME - self.ground_timer.min(MAX_LOCK_TIME));
+
+                if time < left {
+                    self.lock_timer += time;
+                    self.ground_timer += time;
+                    break;
+                }
+
+                time -= left;
+                self.lock_piece();
+            } else {
+                let left = self.drop_interval() - self.drop_timer.min(self.drop_interval());
+
+                if time < left {
+                    self.drop_timer += time;
+                    break;
+                }
+
+                time -= left;
+                self.drop_timer = Duration::ZERO;
+
+                self.current_piece.pos.y += 1;
+
+                if self.soft_drop {
+                    self.stats.soft_drop(1);
+                }
+
+                self.last_move = LastMove::Drop;
+
+                self.update_lowest_row();
+            }
+        }
+
+        self.current_piece.draw_pos.y = self.current_piece.pos.y as f32;
+
+        if !self.is_piece_grounded() {
+            self.current_piece.draw_pos.y +=
+                self.drop_timer.as_secs_f32() / self.drop_interval().as_secs_f32();
+        }
+    }
+
+    fn is_piece_grounded(&self) -> bool {
+        self.current_piece.pos.y >= self.current_piece.drop_pos
+    }
+
+    /// Gives the lock delay and its resets back when the piece reaches a row
+    /// it hasn't been on before.
+    fn update_lowest_row(&mut self) {
+        if self.current_piece.pos.y > self.lowest_row {
+            self.lowest_row = self.current_piece.pos.y;
+            self.lock_timer = Duration::ZERO;
+            self.lock_resets = 0;
+        }
+    }
+
+    /// Called after every successful move or rotation, restarts the lock
+    /// delay if the piece has touched the ground and has resets left.
+    fn reset_lock_delay(&mut self) {
+        self.update_lowest_row();
+
+        if self.ground_timer > Duration::ZERO && self.lock_resets < MAX_LOCK_RESETS {
+            self.lock_timer = Duration::ZERO;
+            self.lock_resets += 1;
+        }
+    }
+
+    pub fn lock_delay(&self) -> Duration {
+        self.lock_delay
+    }
+
+    /// Sets how long a piece can rest on the ground before it locks.
+    pub fn set_lock_delay(&mut self, lock_delay: Duration) {
+        self.lock_delay = lock_delay;
+    }
+
+    pub fn soft_drop_factor(&self) -> u32 {
+        self.soft_drop_factor
+    }
+
+    /// Sets how many times faster than gravity the piece falls while soft
+    /// dropping, at least 1.
+    pub fn set_soft_drop_factor(&mut self, soft_drop_factor: u32) {
+        self.soft_drop_factor = soft_drop_factor.max(1);
+    }
+
+    fn drop_interval(&self) -> Duration {
+        if self.soft_drop {
+            self.stats.gravity() / self.soft_drop_factor
+        } else {
+            self.stats.gravity()
+        }
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Spawns the first piece like every other one, it waits on the spawn
+    /// row while the game is ready.
+    pub fn start(&mut self) {
+        if self.state == GameState::Ready {
+            self.spawn_piece(self.current_piece.piece_type, self.current_seed);
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.state = match self.state {
+            GameState::Playing => GameState::Paused,
+            GameState::Paused => GameState::Playing,
+            state => state,
+        };
+    }
+
+    /// Empties the grid and goes back to `GameState::Ready`, the piece
+    /// generator keeps its sequence going.
+    pub fn restart(&mut self) {
+        self.grid.fill(EMPTY_BLOCK);
+        self.grid_revision += 1;
+
+        self.state = GameState::Ready;
+
+        self.drop_timer = Duration::ZERO;
+        self.line_clear_timer = Duration::ZERO;
+
+        self.lock_timer = Duration::ZERO;
+        self.ground_timer = Duration::ZERO;
+        self.lock_resets = 0;
+        self.lowest_row = -2;
+
+        self.last_move = LastMove::None;
+        self.last_lock = LockResult::new();
+
+        self.events.clear();
+
+        self.held_piece = None;
+        self.hold_used = false;
+
+        self.stats = Stats::new();
+
+        let (piece_type, seed) = self.next_piece();
+
+        self.current_seed = seed;
+        self.current_piece = DroppingPiece::new(
+            glm::vec2(self.grid_width as i32 / 2, spawn_row(piece_type)),
+            piece_type,
+        );
+
+        self.update_dropped_pos();
+    }
+
+    /// How far the current line clear animation is, from 0 to 1.
+    pub fn line_clear_progress(&self) -> f32 {
+        match self.state {
+            GameState::LineClearAnimating => {
+                (self.line_clear_timer.as_secs_f32() / LINE_CLEAR_TIME.as_secs_f32()).min(1.0)
+            }
+            _ => 0.0,
+        }
+    }
+
+    pub fn grid(&self) -> &[SeedT] {
+        &self.grid
+    }
+
+    pub fn current_piece(&self) -> &DroppingPiece {
+        &self.current_piece
+    }
+
+    /// Seed of the color of the current piece, its cells keep it on the
+    /// grid.
+    pub fn current_seed(&self) -> SeedT {
+        self.current_seed
+    }
+
+    pub fn held_piece(&self) -> Option<usize> {
+        self.held_piece.map(|(piece_type, _)| piece_type)
+    }
+
+    /// Upcoming piece types, the first one spawns next.
+    pub fn preview(&self) -> impl Iterator<Item = usize> + '_ {
+        self.preview
+            .iter()
+            .map(|&(piece_type, _)| piece_type)
+            .take(self.preview_len)
+    }
+
+    pub fn preview_len(&self) -> usize {
+        self.preview_len
+    }
+
+    /// Sets how many upcoming pieces `preview` shows, clamped to
+    /// `1..=MAX_PREVIEW_LEN`.
+    pub fn set_preview_len(&mut self, len: usize) {
+        self.preview_len = len.clamp(1, MAX_PREVIEW_LEN);
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Rows cleared by the last locked piece, empty if it cleared nothing.
+    /// During `GameState::LineClearAnimating` they are still on the grid.
+    pub fn cleared_rows(&self) -> &[usize] {
+        &self.last_lock.rows
+    }
+
+    pub fn last_lock(&self) -> &LockResult {
+        &self.last_lock
+    }
+
+    /// Takes the events since the last call, oldest first. Front-ends should
+    /// drain them every frame, past `MAX_QUEUED_EVENTS` the oldest are lost.
+    pub fn drain_events(&mut self) -> std::collections::vec_deque::Drain<'_, GameEvent> {
+        self.events.drain(..)
+    }
+
+    fn push_event(&mut self, event: GameEvent) {
+        if self.events.len() == MAX_QUEUED_EVENTS {
+            self.events.pop_front();
+        }
+
+        self.events.push_back(event);
+    }
+
+    pub fn last_move(&self) -> LastMove {
+        self.last_move
+    }
+
+    pub fn draw_grid_with(&self, func: impl Fn(f32, f32, Option<glm::Vec4>)) {
+        self.draw_cells_with(|x, y, color| func(x as f32, y as f32, color));
+        self.draw_piece_with(func);
+    }
+
+    /// Calls `func` with the column, row and color of every grid cell, the
+    /// result only changes with `grid_revision` or during line clears.
+    pub fn draw_cells_with(&self, func: impl Fn(usize, usize, Option<glm::Vec4>)) {
+        let flash = self.line_clear_progress();
+
+        for (i, it) in self.grid.iter().enumerate() {
+            let (x, y) = (i % self.grid_width, i / self.grid_width);
+
+            let mut color = generate_color(*it, 1.0);
+
+            if flash > 0.0 && self.last_lock.rows.contains(&y) {
+                color = color.map(|c| glm::lerp(&c, &glm::vec4(1.0, 1.0, 1.0, 0.0), flash));
+            }
+
+            func(x, y, color);
+        }
+    }
+
+    /// Calls `func` for every block of the current piece and its ghost, in the
+    /// same space as `draw_grid_with`. The columns are not wrapped, so they
+    /// don't jump when the piece goes around the cylinder.
+    pub fn draw_piece_with(&self, func: impl Fn(f32, f32, Option<glm::Vec4>)) {
+        if self.state == GameState::LineClearAnimating {
+            // the piece is already part of the grid
+            return;
+        }
+
+        self.current_piece
+            .draw_with(|v| func(v.x, v.y, generate_color(self.current_seed, 1.0)));
+        self.current_piece
+            .draw_dropped_with(|v| func(v.x, v.y, generate_color(self.current_seed, 0.5)));
+    }
+
+    /// Goes up every time a cell of the grid changes.
+    pub fn grid_revision(&self) -> u64 {
+        self.grid_revision
+    }
+
+    /// Calls `func` for every block of the held piece, the columns are
+    /// relative to the one facing the camera. It is faded out while it cannot
+    /// be swapped.
+    pub fn draw_hold_with(&self, func: impl Fn(f32, f32, Option<glm::Vec4>)) {
+        if let Some((held, seed)) = self.held_piece {
+            let alpha = if self.hold_used { 0.3 } else { 0.8 };
+            let color = generate_color(seed, alpha);
+
+            DroppingPiece::new(glm::vec2(0, 0), held)
+                .draw_with(|v| func(v.x + HOLD_DRAW_POS.0, v.y + HOLD_DRAW_POS.1, color));
+        }
+    }
+
+    /// Calls `func` with the preview index and the block offset inside the
+    /// piece for every block of the preview queue, the renderer decides
+    /// where the pieces go.
+    pub fn draw_preview_with(&self, func: impl Fn(usize, glm::Vec2, Option<glm::Vec4>)) {
+        let preview = self.preview.iter().take(self.preview_len);
+
+        for (i, &(piece_type, seed)) in preview.enumerate() {
+            let color = generate_color(seed, 0.8);
+
+            DroppingPiece::new(glm::vec2(0, 0), piece_type).draw_with(|v| func(i, v, color));
+        }
+    }
+
+    pub fn move_piece(&mut self, movement: i32) {
+        if self.state != GameState::Playing {
+            return;
+        }
+
+        self.current_piece.pos.x += movement;
+
+        if self.is_piece_collided() {
+            self.current_piece.pos.x -= movement;
+        } else {
+            self.update_dropped_pos();
+            self.reset_lock_delay();
+
+            self.last_move = LastMove::Shift;
+            self.push_event(GameEvent::Moved { movement });
+        }
+    }
+
+    pub fn toggle_piece_drop(&mut self, start_drop: bool) {
+        self.soft_drop = start_drop;
+    }
+
+    pub fn hard_drop_piece(&mut self) {
+        if self.state != GameState::Playing {
+            return;
+        }
+
+        let distance = self.current_piece.drop_pos - self.current_piece.pos.y;
+
+        self.stats.hard_drop(distance.max(0) as u32);
+
+        self.push_event(GameEvent::HardDropped {
+            distance: distance.max(0) as u32,
+        });
+
+        if distance > 0 {
+            self.last_move = LastMove::Drop;
+        }
+
+        self.current_piece.pos.y = self.current_piece.drop_pos;
+        self.lock_piece();
+    }
+
+    /// Stashes the current piece and continues with the held one, or with
+    /// the next piece when the slot is empty. Works once per locked piece.
+    pub fn hold_piece(&mut self) {
+        if self.state != GameState::Playing || self.hold_used {
+            return;
+        }
+
+        let current = self.current_piece.piece_type;
+
+        let (next, seed) = match self.held_piece.replace((current, self.current_seed)) {
+            Some(held) => held,
+            None => self.next_piece(),
+        };
+
+        self.hold_used = true;
+        self.push_event(GameEvent::Held {
+            piece_type: current,
+        });
+
+        self.spawn_piece(next, seed);
+    }
+
+    /// Rotates the piece with SRS wall kicks, returns the index of the kick
+    /// test that succeeded or `None` if the piece couldn't rotate.
+    pub fn rotate_piece(&mut self, rotation: Rotation) -> Option<usize> {
+        if self.state != GameState::Playing {
+            return None;
+        }
+
+        if self.current_piece.piece_type == O_TETROMINO {
+            // O block doesnt need any rotation
+            return None;
+        }
+
+        let (old_pos, old_rotation) = (self.current_piece.pos, self.current_piece.rotation);
+
+        self.current_piece.rotate(rotation);
+
+        let kicks = rotation::kicks(
+            self.current_piece.piece_type,
+            old_rotation,
+            self.current_piece.rotation,
+        );
+
+        for (i, kick) in kicks.enumerate() {
+            self.current_piece.pos = old_pos + kick;
+
+            if !self.is_piece_collided() {
+                self.update_dropped_pos();
+                self.reset_lock_delay();
+
+                self.last_move = LastMove::Rotation { kick: i };
+                self.push_event(GameEvent::Rotated { rotation, kick: i });
+
+                return Some(i);
+            }
+        }
+
+        self.current_piece.pos = old_pos;
+        self.current_piece.rotation = old_rotation;
+
+        None
+    }
+
+    fn lock_piece(&mut self) {
+        let spin = self.detect_spin();
+
+        // lock out, part of the piece is above the grid
+        let locked_out = (0..4).any(|i| self.current_piece.get_pos_at(i).y < 0);
+
+        self.place_piece_to_grid();
+
+        self.hold_used = false;
+
+        let rows = self.find_full_rows();
+        let perfect_clear = !rows.is_empty() && self.is_grid_empty_without(&rows);
+
+        self.push_event(GameEvent::Locked {
+            spin,
+            perfect_clear,
+        });
+
+        if locked_out {
+            self.game_over();
+            return;
+        }
+
+        if !rows.is_empty() {
+            self.push_event(GameEvent::LinesCleared { rows: rows.clone() });
+        }
+
+        if self.stats.lock(rows.len(), spin, perfect_clear) {
+            self.push_event(GameEvent::LevelUp {
+                level: self.stats.level,
+            });
+        }
+
+        self.last_lock = LockResult {
+            rows,
+            spin,
+            perfect_clear,
+        };
+
+        if self.last_lock.rows.is_empty() {
+            self.spawn_next_piece();
+        } else {
+            self.line_clear_timer = Duration::ZERO;
+            self.state = GameState::LineClearAnimating;
+        }
+    }
+
+    /// Takes the first piece of the preview queue with its seed and refills
+    /// it.
+    fn next_piece(&mut self) -> (usize, SeedT) {
+        self.preview
+            .push_back((self.generator.next_piece(), self.next_seed));
+        self.next_seed += 1;
+
+        self.preview.pop_front().unwrap()
+    }
+
+    fn spawn_next_piece(&mut self) {
+        let (piece_type, seed) = self.next_piece();
+
+        self.spawn_piece(piece_type, seed);
+    }
+
+    fn spawn_piece(&mut self, piece_type: usize, seed: SeedT) {
+        let spawn_row = spawn_row(piece_type);
+
+        self.current_seed = seed;
+        self.current_piece.renew(spawn_row, piece_type);
+
+        self.drop_timer = Duration::ZERO;
+
+        self.lock_timer = Duration::ZERO;
+        self.ground_timer = Duration::ZERO;
+        self.lock_resets = 0;
+        self.lowest_row = spawn_row;
+
+        self.last_move = LastMove::None;
+
+        self.push_event(GameEvent::PieceSpawned { piece_type });
+
+        // block out, the new piece overlaps the stack
+        if self.is_piece_collided() {
+            self.game_over();
+            return;
+        }
+
+        self.state = GameState::Playing;
+        self.update_dropped_pos();
+    }
+
+    fn game_over(&mut self) {
+        self.state = GameState::GameOver;
+        self.push_event(GameEvent::GameOver);
+    }
+
+    fn update_dropped_pos(&mut self) {
+        let old_pos = self.current_piece.pos.y;
+
+        for y in old_pos + 1..self.grid_height as i32 + 1 {
+            self.current_piece.pos.y = y;
+
+            if self.is_piece_collided() {
+                self.current_piece.drop_pos = self.current_piece.pos.y - 1;
+                break;
+            }
+        }
+
+        self.current_piece.pos.y = old_pos;
+    }
+
+    fn is_piece_collided(&self) -> bool {
+        (0..4).any(|i| {
+            let pos = self.current_piece.get_pos_at(i);
+
+            // nothing is above the grid, pieces are free to stick out of it
+            pos.y >= 0 && self.at_grid(pos) != Some(EMPTY_BLOCK)
+        })
+    }
+
+    pub fn at_grid(&self, v: Vec2) -> Option<SeedT> {
+        if v.y >= 0 && v.y < self.grid_height as i32 {
+            let x = v.x.rem_euclid(self.grid_width as i32) as usize;
+
+            Some(self.grid[(v.y as usize * self.grid_width) + x])
+        } else {
+            None
+        }
+    }
+
+    /// Writes a cell, columns wrap around and rows outside the grid are
+    /// ignored. Meant for setting up boards, the current piece isn't checked
+    /// against it.
+    pub fn set_grid(&mut self, v: Vec2, value: SeedT) {
+        if v.y >= 0 && v.y < self.grid_height as i32 {
+            let x = v.x.rem_euclid(self.grid_width as i32) as usize;
+
+            self.grid[(v.y as usize * self.grid_width) + x] = value;
+            self.grid_revision += 1;
+        }
+    }
+
+    fn place_piece_to_grid(&mut self) {
+        for i in 0..4 {
+            self.set_grid(self.current_piece.get_pos_at(i), self.current_seed);
+        }
+    }
+
+    fn is_layer_full(&self, y: usize) -> bool {
+        let index = y * self.grid_width;
+
+        for i in 0..self.grid_width {
+            if self.grid[index + i] == EMPTY_BLOCK {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Every full layer of the cylinder, from top to bottom.
+    fn find_full_rows(&self) -> Vec<usize> {
+        (0..self.grid_height)
+            .filter(|&y| self.is_layer_full(y))
+            .collect()
+    }
+
+    fn is_grid_empty_without(&self, rows: &[usize]) -> bool {
+        self.grid
+            .chunks(self.grid_width)
+            .enumerate()
+            .all(|(y, row)| rows.contains(&y) || row.iter().all(|&it| it == EMPTY_BLOCK))
+    }
+
+    /// 3-corner rule, a T piece that locks right after a rotation with three
+    /// of the four cells diagonal to its pivot filled did a T-spin. It is a
+    /// mini unless both corners it points to are filled or the rotation used
+    /// the last kick. The floor counts as filled, and since columns wrap
+    /// around there are no walls to count.
+    fn detect_spin(&self) -> Spin {
+        let piece = &self.current_piece;
+
+        let kick = match self.last_move {
+            LastMove::Rotation { kick } if piece.piece_type == T_TETROMINO => kick,
+            _ => return Spin::None,
+        };
+
+        let is_filled = |v: Vec2| {
+            let pos = piece.pos + v;
+
+            pos.y >= 0 && self.at_grid(pos) != Some(EMPTY_BLOCK)
+        };
+
+        // the T points up in the spawn state
+        let facing = rotation::rotation_matrix(piece.rotation) * Vec2::new(0, -1);
+        let side = Vec2::new(facing.y, facing.x);
+
+        let front = [facing + side, facing - side]
+            .iter()
+            .filter(|&&v| is_filled(v))
+            .count();
+        let back = [side - facing, -side - facing]
+            .iter()
+            .filter(|&&v| is_filled(v))
+            .count();
+
+        if front + back < 3 {
+            Spin::None
+        } else if front == 2 || kick == 4 {
+            Spin::Full
+        } else {
+            Spin::Mini
+        }
+    }
+
+    /// Removes the cleared rows and moves everything above them down.
+    fn remove_cleared_rows(&mut self) {
+        let cleared_rows = &self.last_lock.rows;
+
+        let width = self.grid_width;
+        let mut target = self.grid_height;
+
+        for y in (0..self.grid_height).rev() {
+            if cleared_rows.contains(&y) {
+                continue;
+            }
+
+            target -= 1;
+
+            if target != y {
+                self.grid
+                    .copy_within(y * width..(y + 1) * width, target * width);
+            }
+        }
+
+        self.grid[..target * width].fill(EMPTY_BLOCK);
+        self.grid_revision += 1;
+    }
+}
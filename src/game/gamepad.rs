@@ -0,0 +1,231 @@
+use super::bindings::Action;
+use super::config::{self, ConfigError};
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+const DEFAULT_DEADZONE: f32 = 0.2;
+
+/// The buttons of a gamepad in the standard layout, in the order glfw
+/// reports them. The face buttons are named after their xbox labels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Button {
+    A,
+    B,
+    X,
+    Y,
+    LeftBumper,
+    RightBumper,
+    Back,
+    Start,
+    Guide,
+    LeftThumb,
+    RightThumb,
+    DpadUp,
+    DpadRight,
+    DpadDown,
+    DpadLeft,
+}
+
+pub const BUTTON_COUNT: usize = 15;
+
+/// The buttons that trigger an action, the d-pad works like the arrow keys.
+const BUTTONS: [(Button, Action); 12] = [
+    (Button::DpadLeft, Action::MoveLeft),
+    (Button::DpadRight, Action::MoveRight),
+    (Button::DpadDown, Action::SoftDrop),
+    (Button::DpadUp, Action::HardDrop),
+    (Button::A, Action::RotateCcw),
+    (Button::B, Action::RotateCw),
+    (Button::Y, Action::Rotate180),
+    (Button::X, Action::Hold),
+    (Button::LeftBumper, Action::Hold),
+    (Button::RightBumper, Action::Hold),
+    (Button::Start, Action::Confirm),
+    (Button::Back, Action::Pause),
+];
+
+/// One poll of a gamepad. The sticks go from -1 to 1 on both axes, with y
+/// pointing down.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PadState {
+    /// Indexed by `Button`.
+    pub buttons: [bool; BUTTON_COUNT],
+    pub left_stick: (f32, f32),
+    pub right_stick: (f32, f32),
+}
+
+impl PadState {
+    pub fn is_pressed(&self, button: Button) -> bool {
+        self.buttons[button as usize]
+    }
+}
+
+/// Turns the polls of one connected gamepad into presses and releases of
+/// actions, the same ones the keys trigger.
+pub struct Gamepad {
+    deadzone: f32,
+
+    /// Actions held down in the last poll.
+    held: BTreeSet<Action>,
+    right_stick: (f32, f32),
+}
+
+impl Gamepad {
+    /// Stick movements up to `deadzone` of their travel are ignored.
+    pub fn new(deadzone: f32) -> Self {
+        Gamepad {
+            deadzone,
+
+            held: BTreeSet::new(),
+            right_stick: (0.0, 0.0),
+        }
+    }
+
+    pub fn deadzone(&self) -> f32 {
+        self.deadzone
+    }
+
+    /// The actions pressed (`true`) and released (`false`) since the last
+    /// poll, the releases come first. Several buttons of one action only
+    /// press it once. Out of the deadzone the left stick works like the
+    /// d-pad, without the hard drop.
+    pub fn poll(&mut self, state: &PadState) -> Vec<(Action, bool)> {
+        let mut held: BTreeSet<Action> = BUTTONS
+            .iter()
+            .filter(|(button, _)| state.is_pressed(*button))
+            .map(|(_, action)| *action)
+            .collect();
+
+        let (x, y) = apply_deadzone(state.left_stick, self.deadzone);
+
+        if x.abs() > y.abs() {
+            held.insert(if x < 0.0 {
+                Action::MoveLeft
+            } else {
+                Action::MoveRight
+            });
+        } else if y > 0.0 {
+            held.insert(Action::SoftDrop);
+        }
+
+        self.right_stick = apply_deadzone(state.right_stick, self.deadzone);
+
+        let changes = self
+            .held
+            .difference(&held)
+            .map(|&action| (action, false))
+            .chain(held.difference(&self.held).map(|&action| (action, true)))
+            .collect();
+
+        self.held = held;
+
+        changes
+    }
+
+    /// The right stick of the last poll with the deadzone taken out, scaled
+    /// back to the full -1 to 1 range.
+    pub fn right_stick(&self) -> (f32, f32) {
+        self.right_stick
+    }
+
+    /// Releases everything held, for when the gamepad is unplugged.
+    pub fn disconnect(&mut self) -> Vec<Action> {
+        self.right_stick = (0.0, 0.0);
+
+        std::mem::take(&mut self.held).into_iter().collect()
+    }
+}
+
+/// A radial deadzone, so diagonals aren't cut off more than the axes.
+fn apply_deadzone((x, y): (f32, f32), deadzone: f32) -> (f32, f32) {
+    let length = x.hypot(y);
+
+    if length <= deadzone {
+        return (0.0, 0.0);
+    }
+
+    let scale = (length.min(1.0) - deadzone) / (1.0 - deadzone) / length;
+
+    (x * scale, y * scale)
+}
+
+/// The deadzones of the gamepads, devices can have their own.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GamepadSettings {
+    pub deadzone: f32,
+    /// Deadzones by the gamepad name glfw reports.
+    pub devices: HashMap<String, f32>,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        GamepadSettings {
+            deadzone: DEFAULT_DEADZONE,
+            devices: HashMap::new(),
+        }
+    }
+}
+
+impl GamepadSettings {
+    /// Reads the settings from a toml file, a missing file gives the defaults.
+    pub fn load(file_path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        config::load_table(file_path)?.map_or(Ok(Self::default()), Self::from_table)
+    }
+
+    /// `deadzone = 0.2` sets it for every gamepad, a `[devices."<name>"]`
+    /// table with its own `deadzone` overrides it for one.
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        Self::from_table(config::parse_table(source)?)
+    }
+
+    fn from_table(table: toml::Table) -> Result<Self, ConfigError> {
+        let mut settings = GamepadSettings::default();
+
+        for (name, value) in table {
+            match name.as_str() {
+                "deadzone" => settings.deadzone = deadzone(&name, &value)?,
+                "devices" => {
+                    let devices = value
+                        .as_table()
+                        .ok_or_else(|| ConfigError::InvalidValue(name.clone()))?;
+
+                    for (device, value) in devices {
+                        let device_table = value
+                            .as_table()
+                            .ok_or_else(|| ConfigError::InvalidValue(device.clone()))?;
+
+                        for (setting, value) in device_table {
+                            match setting.as_str() {
+                                "deadzone" => {
+                                    let deadzone = deadzone(device, value)?;
+
+                                    settings.devices.insert(device.clone(), deadzone);
+                                }
+                                _ => return Err(ConfigError::UnknownSetting(setting.clone())),
+                            }
+                        }
+                    }
+                }
+                _ => return Err(ConfigError::UnknownSetting(name)),
+            }
+        }
+
+        Ok(settings)
+    }
+
+    /// The deadzone of the gamepad called `name`.
+    pub fn deadzone_for(&self, name: &str) -> f32 {
+        self.devices.get(name).copied().unwrap_or(self.deadzone)
+    }
+}
+
+/// A deadzone has to leave some of the stick travel.
+fn deadzone(name: &str, value: &toml::Value) -> Result<f32, ConfigError> {
+    value
+        .as_float()
+        .or_else(|| value.as_integer().map(|value| value as f64))
+        .filter(|value| (0.0..1.0).contains(value))
+        .map(|value| value as f32)
+        .ok_or_else(|| ConfigError::InvalidValue(name.to_string()))
+}
@@ -0,0 +1,195 @@
+use super::bindings::Action;
+use super::config::{self, ConfigError};
+use super::game_logic::{GameLogic, GameState};
+use super::input::Command;
+
+use std::convert::TryFrom;
+use std::path::Path;
+use std::time::Duration;
+
+/// How held keys behave, each player can tune these to their taste.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Handling {
+    /// Delayed auto shift, how long a move has to be held before it repeats.
+    pub das: Duration,
+    /// Auto repeat rate, the time between the repeated moves. Zero moves the
+    /// piece as far as it goes, at most once around the cylinder, once per
+    /// press.
+    pub arr: Duration,
+    /// How many times faster than gravity soft dropping is.
+    pub soft_drop_factor: u32,
+}
+
+impl Default for Handling {
+    fn default() -> Self {
+        Handling {
+            das: Duration::from_millis(167),
+            arr: Duration::from_millis(33),
+            soft_drop_factor: 4,
+        }
+    }
+}
+
+impl Handling {
+    /// Reads the handling from a toml file, a missing file gives the defaults.
+    pub fn load(file_path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        config::load_table(file_path)?.map_or(Ok(Self::default()), Self::from_table)
+    }
+
+    /// `das` and `arr` are in milliseconds, e.g. `das = 120`. The settings
+    /// left out keep their defaults.
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        Self::from_table(config::parse_table(source)?)
+    }
+
+    fn from_table(table: toml::Table) -> Result<Self, ConfigError> {
+        let mut handling = Handling::default();
+
+        for (name, value) in table {
+            let value = value
+                .as_integer()
+                .and_then(|value| u32::try_from(value).ok())
+                .ok_or_else(|| ConfigError::InvalidValue(name.clone()))?;
+
+            match name.as_str() {
+                "das" => handling.das = Duration::from_millis(value.into()),
+                "arr" => handling.arr = Duration::from_millis(value.into()),
+                "soft_drop_factor" if value > 0 => handling.soft_drop_factor = value,
+                "soft_drop_factor" => return Err(ConfigError::InvalidValue(name)),
+                _ => return Err(ConfigError::UnknownSetting(name)),
+            }
+        }
+
+        Ok(handling)
+    }
+}
+
+/// Turns the presses and releases of actions into commands for one player.
+/// Held moves repeat on the game's own clock with the player's `Handling`,
+/// so the key repeat of the os doesn't matter.
+pub struct Controller {
+    handling: Handling,
+
+    /// Directions of the held moves in the order they were pressed, the
+    /// last one is the one that repeats.
+    shifts: Vec<i32>,
+    shift_timer: Duration,
+    repeats: u32,
+}
+
+impl Controller {
+    pub fn new(handling: Handling) -> Self {
+        Controller {
+            handling,
+
+            shifts: Vec::new(),
+            shift_timer: Duration::ZERO,
+            repeats: 0,
+        }
+    }
+
+    pub fn handling(&self) -> Handling {
+        self.handling
+    }
+
+    pub fn set_handling(&mut self, handling: Handling) {
+        self.handling = handling;
+    }
+
+    /// Applies the command of `action` to `game` and returns it, so the
+    /// front-end can handle `Command::Quit`.
+    pub fn press(&mut self, action: Action, game: &mut GameLogic) -> Option<Command> {
+        let command = Command::for_action(action)?;
+
+        match command {
+            Command::Move(direction) => {
+                self.shifts.retain(|&it| it != direction);
+                self.shifts.push(direction);
+
+                self.restart_shift();
+            }
+            Command::SoftDrop(_) => game.set_soft_drop_factor(self.handling.soft_drop_factor),
+            _ => {}
+        }
+
+        command.apply(game);
+
+        Some(command)
+    }
+
+    pub fn release(&mut self, action: Action, game: &mut GameLogic) {
+        match Command::for_action(action) {
+            Some(Command::Move(direction)) => {
+                let repeating = self.shifts.last() == Some(&direction);
+
+                self.shifts.retain(|&it| it != direction);
+
+                // a move still held down starts over, it doesn't jump
+                if repeating {
+                    self.restart_shift();
+                }
+            }
+            Some(Command::SoftDrop(_)) => Command::SoftDrop(false).apply(game),
+            _ => {}
+        }
+    }
+
+    /// Repeats the held move, call it with the same `dt` as `GameLogic::update`.
+    pub fn update(&mut self, dt: Duration, game: &mut GameLogic) {
+        let direction = match self.shifts.last() {
+            Some(&direction) => direction,
+            None => return,
+        };
+
+        // the delay stays charged through line clears, not through pauses
+        if !matches!(
+            game.state(),
+            GameState::Playing | GameState::LineClearAnimating
+        ) {
+            return;
+        }
+
+        self.shift_timer += dt;
+
+        if self.shift_timer < self.handling.das {
+            return;
+        }
+
+        if self.handling.arr == Duration::ZERO {
+            // the cylinder has no walls to stop at, a single shift per press
+            // keeps the piece from going around it every frame
+            if self.repeats > 0 {
+                return;
+            }
+
+            self.repeats = 1;
+
+            for _ in 1..game.grid_width {
+                let x = game.current_piece().pos.x;
+
+                game.move_piece(direction);
+
+                if game.current_piece().pos.x == x {
+                    break;
+                }
+            }
+
+            return;
+        }
+
+        // counted from the start, so the moves don't depend on how dt is split
+        let charged = self.shift_timer - self.handling.das;
+        let repeats = (charged.as_nanos() / self.handling.arr.as_nanos()) as u32 + 1;
+
+        for _ in self.repeats..repeats {
+            game.move_piece(direction);
+        }
+
+        self.repeats = repeats;
+    }
+
+    fn restart_shift(&mut self) {
+        self.shift_timer = Duration::ZERO;
+        self.repeats = 0;
+    }
+}
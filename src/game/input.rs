@@ -0,0 +1,148 @@
+use super::bindings::Action;
+use super::game_logic::{GameLogic, GameState};
+use super::rotation::Rotation;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The keys the game listens to, every front-end converts its own key type
+/// into this so they all share one mapping. Letters are lower case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    Left,
+    Right,
+    Up,
+    Down,
+    Space,
+    Enter,
+    Escape,
+    LeftShift,
+    F1,
+    F11,
+    Char(char),
+}
+
+/// The characters a `Char` can be, the keys every front-end reports: letters,
+/// digits and the punctuation of a us keyboard, unshifted.
+pub const CHAR_KEYS: &str = "abcdefghijklmnopqrstuvwxyz0123456789'`,-./;=[\\]";
+
+const KEY_NAMES: [(Key, &str); 10] = [
+    (Key::Left, "left"),
+    (Key::Right, "right"),
+    (Key::Up, "up"),
+    (Key::Down, "down"),
+    (Key::Space, "space"),
+    (Key::Enter, "enter"),
+    (Key::Escape, "escape"),
+    (Key::LeftShift, "left_shift"),
+    (Key::F1, "f1"),
+    (Key::F11, "f11"),
+];
+
+impl Key {
+    /// Upper case letters become lower case, so shift doesn't change a key.
+    pub fn normalized(self) -> Key {
+        match self {
+            Key::Char(c) => Key::Char(c.to_ascii_lowercase()),
+            key => key,
+        }
+    }
+}
+
+/// The names used in the bindings file, a single character of `CHAR_KEYS` is
+/// a `Char`.
+impl FromStr for Key {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.to_ascii_lowercase();
+
+        if let Some((key, _)) = KEY_NAMES.iter().find(|(_, it)| *it == name) {
+            return Ok(*key);
+        }
+
+        let mut chars = name.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if CHAR_KEYS.contains(c) => Ok(Key::Char(c)),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Key::Char(c) = self {
+            return write!(f, "{}", c);
+        }
+
+        let (_, name) = KEY_NAMES
+            .iter()
+            .find(|(key, _)| key == self)
+            .expect("every other key has a name");
+
+        f.write_str(name)
+    }
+}
+
+/// What a key does to the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Move(i32),
+    /// Starts soft dropping on press, `SoftDrop(false)` comes when the key is
+    /// released.
+    SoftDrop(bool),
+    HardDrop,
+    Rotate(Rotation),
+    Hold,
+    TogglePause,
+    /// Starts the game, or a new one after a game over.
+    Confirm,
+    /// Left to the front-end, the game doesn't know about windows.
+    Quit,
+}
+
+impl Command {
+    /// The command for a pressed key bound to `action`, the camera actions
+    /// have none. Holding a move down is left to `Controller`.
+    pub fn for_action(action: Action) -> Option<Command> {
+        let command = match action {
+            Action::MoveLeft => Command::Move(-1),
+            Action::MoveRight => Command::Move(1),
+            Action::HardDrop => Command::HardDrop,
+            Action::SoftDrop => Command::SoftDrop(true),
+            Action::RotateCw => Command::Rotate(Rotation::Clockwise),
+            Action::RotateCcw => Command::Rotate(Rotation::CounterClockwise),
+            Action::Rotate180 => Command::Rotate(Rotation::Half),
+            Action::Hold => Command::Hold,
+            Action::Pause => Command::TogglePause,
+            Action::Confirm => Command::Confirm,
+            Action::Quit => Command::Quit,
+            _ => return None,
+        };
+
+        Some(command)
+    }
+
+    pub fn apply(self, game: &mut GameLogic) {
+        match self {
+            Command::Move(movement) => game.move_piece(movement),
+            Command::SoftDrop(start_drop) => game.toggle_piece_drop(start_drop),
+            Command::HardDrop => game.hard_drop_piece(),
+            Command::Rotate(rotation) => {
+                game.rotate_piece(rotation);
+            }
+            Command::Hold => game.hold_piece(),
+            Command::TogglePause => game.toggle_pause(),
+            Command::Confirm => match game.state() {
+                GameState::Ready => game.start(),
+                GameState::GameOver => {
+                    game.restart();
+                    game.start();
+                }
+                _ => {}
+            },
+            Command::Quit => {}
+        }
+    }
+}
@@ -0,0 +1,32 @@
+pub mod clock;
+pub use clock::{Clock, ManualClock, SystemClock};
+
+pub mod events;
+pub use events::GameEvent;
+
+pub mod game_logic;
+pub use game_logic::{DroppingPiece, GameLogic, GameState, LastMove, LockResult, TETROMINO_COORDS};
+
+pub mod piece_generator;
+pub use piece_generator::{BagGenerator, PieceGenerator, RandomGenerator, SequenceGenerator};
+
+pub mod rotation;
+pub use rotation::Rotation;
+
+pub mod scoring;
+pub use scoring::{Spin, Stats};
+
+pub mod input;
+pub use input::{Command, Key};
+
+pub mod bindings;
+pub use bindings::{Action, KeyBindings};
+
+pub mod config;
+pub use config::ConfigError;
+
+pub mod handling;
+pub use handling::{Controller, Handling};
+
+pub mod gamepad;
+pub use gamepad::{Gamepad, GamepadSettings, PadState};
@@ -0,0 +1,84 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::game_logic::TETROMINO_COORDS;
+
+/// Source of the piece types handed to `GameLogic`, every call returns an
+/// index into `TETROMINO_COORDS`.
+pub trait PieceGenerator {
+    fn next_piece(&mut self) -> usize;
+}
+
+/// Seeded 7-bag randomizer, deals every tetromino once in a shuffled order
+/// before refilling, which keeps droughts at most 12 pieces long.
+pub struct BagGenerator {
+    rng: StdRng,
+    bag: Vec<usize>,
+}
+
+impl BagGenerator {
+    pub fn new(seed: u64) -> Self {
+        BagGenerator {
+            rng: StdRng::seed_from_u64(seed),
+            bag: Vec::with_capacity(TETROMINO_COORDS.len()),
+        }
+    }
+}
+
+impl PieceGenerator for BagGenerator {
+    fn next_piece(&mut self) -> usize {
+        if self.bag.is_empty() {
+            self.bag.extend(0..TETROMINO_COORDS.len());
+            self.bag.shuffle(&mut self.rng);
+        }
+
+        self.bag.pop().unwrap()
+    }
+}
+
+/// Seeded memoryless randomizer, every piece type is equally likely on
+/// every draw.
+pub struct RandomGenerator {
+    rng: StdRng,
+}
+
+impl RandomGenerator {
+    pub fn new(seed: u64) -> Self {
+        RandomGenerator {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl PieceGenerator for RandomGenerator {
+    fn next_piece(&mut self) -> usize {
+        self.rng.gen_range(0..TETROMINO_COORDS.len())
+    }
+}
+
+/// Repeats a fixed list of piece types, mostly useful for tests.
+pub struct SequenceGenerator {
+    pieces: Vec<usize>,
+    index: usize,
+}
+
+impl SequenceGenerator {
+    pub fn new(pieces: Vec<usize>) -> Self {
+        assert!(!pieces.is_empty(), "piece sequence cannot be empty");
+        assert!(
+            pieces.iter().all(|&piece| piece < TETROMINO_COORDS.len()),
+            "invalid piece type in sequence"
+        );
+
+        SequenceGenerator { pieces, index: 0 }
+    }
+}
+
+impl PieceGenerator for SequenceGenerator {
+    fn next_piece(&mut self) -> usize {
+        let piece = self.pieces[self.index];
+
+        self.index = (self.index + 1) % self.pieces.len();
+
+        piece
+    }
+}
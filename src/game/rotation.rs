@@ -0,0 +1,73 @@
+//! Super Rotation System, written with the offset tables from the guideline
+//! instead of the kick tables. The kicks of a rotation are the offsets of
+//! the old state minus the offsets of the new state, which also gives 180
+//! degree rotations their kicks.
+//!
+//! The grid is a cylinder, so there are no walls and horizontal kicks just
+//! move the piece around it; only the floor and the stack can reject a test.
+
+use nalgebra_glm as glm;
+
+use super::game_logic::{Mat2, Vec2, I_TETROMINO};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+    Half,
+}
+
+impl Rotation {
+    /// Rotation state after rotating from `state`, states go clockwise from
+    /// 0 (spawn) to 3.
+    pub fn apply(self, state: usize) -> usize {
+        let quarter_turns = match self {
+            Rotation::Clockwise => 1,
+            Rotation::Half => 2,
+            Rotation::CounterClockwise => 3,
+        };
+
+        (state + quarter_turns) % 4
+    }
+}
+
+/// Matrix that turns the spawn coordinates into the given rotation state,
+/// clockwise on screen since y grows downwards.
+pub fn rotation_matrix(state: usize) -> Mat2 {
+    match state % 4 {
+        0 => glm::mat2(1, 0, 0, 1),
+        1 => glm::mat2(0, -1, 1, 0),
+        2 => glm::mat2(-1, 0, 0, -1),
+        _ => glm::mat2(0, 1, -1, 0),
+    }
+}
+
+// offsets per rotation state with y pointing up, as they are usually written
+const JLSTZ_OFFSETS: [[(i32, i32); 5]; 4] = [
+    [(0, 0), (0, 0), (0, 0), (0, 0), (0, 0)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (0, 0), (0, 0), (0, 0), (0, 0)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+];
+
+const I_OFFSETS: [[(i32, i32); 5]; 4] = [
+    [(0, 0), (-1, 0), (2, 0), (-1, 0), (2, 0)],
+    [(-1, 0), (0, 0), (0, 0), (0, 1), (0, -2)],
+    [(-1, 1), (1, 1), (-2, 1), (1, 0), (-2, 0)],
+    [(0, 1), (0, 1), (0, 1), (0, -1), (0, 2)],
+];
+
+/// Translations to test in order when rotating `piece_type` from one state
+/// to another, the first one that doesn't collide wins. The O piece is
+/// special cased by `GameLogic` and never gets here.
+pub fn kicks(piece_type: usize, from: usize, to: usize) -> impl Iterator<Item = Vec2> {
+    let offsets = if piece_type == I_TETROMINO {
+        &I_OFFSETS
+    } else {
+        &JLSTZ_OFFSETS
+    };
+
+    let (from, to) = (offsets[from % 4], offsets[to % 4]);
+
+    (0..5).map(move |i| Vec2::new(from[i].0 - to[i].0, to[i].1 - from[i].1))
+}
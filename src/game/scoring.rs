@@ -0,0 +1,263 @@
+use std::time::Duration;
+
+pub const LINES_PER_LEVEL: u32 = 10;
+
+/// Milliseconds a piece takes to fall one row, indexed by `level - 1`.
+/// Levels past the end of the table keep its last speed.
+const LEVEL_SPEEDS: [u64; 15] = [
+    1000, 793, 618, 473, 355, 262, 190, 135, 94, 64, 43, 28, 18, 11, 7,
+];
+
+/// Base points for clearing 0 to 4 lines, per kind of spin. Everything is
+/// multiplied by the level.
+const LINE_CLEAR_POINTS: [u64; 5] = [0, 100, 300, 500, 800];
+const MINI_T_SPIN_POINTS: [u64; 5] = [100, 200, 400, 400, 400];
+const T_SPIN_POINTS: [u64; 5] = [400, 800, 1200, 1600, 1600];
+
+/// Bonus for emptying the whole grid, by lines cleared.
+const PERFECT_CLEAR_POINTS: [u64; 5] = [0, 800, 1200, 1800, 2000];
+
+const COMBO_POINTS: u64 = 50;
+const SOFT_DROP_POINTS: u64 = 1;
+const HARD_DROP_POINTS: u64 = 2;
+
+/// T-spin found by the 3-corner rule when a T piece locks right after a
+/// rotation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Spin {
+    None,
+    Mini,
+    Full,
+}
+
+/// Score, level and line counter of a game. `GameLogic` keeps it up to date
+/// and hands it out read-only.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub score: u64,
+    pub level: u32,
+    pub lines: u32,
+
+    /// Consecutive locks that cleared lines, minus one. `None` when the last
+    /// lock cleared nothing.
+    pub combo: Option<u32>,
+    /// Whether the last clear was a difficult one, the next difficult clear
+    /// then gets the back-to-back bonus.
+    pub back_to_back: bool,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats {
+            score: 0,
+            level: 1,
+            lines: 0,
+
+            combo: None,
+            back_to_back: false,
+        }
+    }
+
+    /// Time a piece takes to fall one row at the current level.
+    pub fn gravity(&self) -> Duration {
+        let index = (self.level as usize - 1).min(LEVEL_SPEEDS.len() - 1);
+
+        Duration::from_millis(LEVEL_SPEEDS[index])
+    }
+
+    pub(crate) fn soft_drop(&mut self, distance: u32) {
+        self.score += SOFT_DROP_POINTS * distance as u64;
+    }
+
+    pub(crate) fn hard_drop(&mut self, distance: u32) {
+        self.score += HARD_DROP_POINTS * distance as u64;
+    }
+
+    /// Awards a locked piece that cleared `lines` rows, zero included since
+    /// it breaks the combo. Returns whether the level went up.
+    pub(crate) fn lock(&mut self, lines: usize, spin: Spin, perfect_clear: bool) -> bool {
+        let lines = lines.min(4);
+        let level = self.level as u64;
+
+        let points = match spin {
+            Spin::None => LINE_CLEAR_POINTS[lines],
+            Spin::Mini => MINI_T_SPIN_POINTS[lines],
+            Spin::Full => T_SPIN_POINTS[lines],
+        };
+
+        if lines == 0 {
+            self.score += points * level;
+            self.combo = None;
+            return false;
+        }
+
+        // tetrises and spins that clear lines keep back-to-back going
+        let difficult = lines == 4 || spin != Spin::None;
+
+        let mut points = points * level;
+
+        if difficult && self.back_to_back {
+            points += points / 2;
+        }
+
+        self.back_to_back = difficult;
+
+        if perfect_clear {
+            points += PERFECT_CLEAR_POINTS[lines] * level;
+        }
+
+        let combo = self.combo.map_or(0, |combo| combo + 1);
+
+        self.combo = Some(combo);
+        self.score += points + COMBO_POINTS * combo as u64 * level;
+
+        self.lines += lines as u32;
+
+        let new_level = self.lines / LINES_PER_LEVEL + 1;
+
+        if new_level > self.level {
+            self.level = new_level;
+            return true;
+        }
+
+        false
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stats after a lock that cleared nothing, so no combo carries over.
+    fn stats_at(level: u32) -> Stats {
+        Stats {
+            level,
+            lines: (level - 1) * LINES_PER_LEVEL,
+            ..Stats::new()
+        }
+    }
+
+    fn points(stats: &mut Stats, lines: usize, spin: Spin) -> u64 {
+        let score = stats.score;
+
+        stats.lock(lines, spin, false);
+        stats.combo = None;
+
+        stats.score - score
+    }
+
+    #[test]
+    fn line_clears_score_the_guideline_table_times_the_level() {
+        let table = [
+            (0, Spin::None, 0),
+            (1, Spin::None, 100),
+            (2, Spin::None, 300),
+            (3, Spin::None, 500),
+            (4, Spin::None, 800),
+            (0, Spin::Mini, 100),
+            (1, Spin::Mini, 200),
+            (0, Spin::Full, 400),
+            (1, Spin::Full, 800),
+            (2, Spin::Full, 1200),
+            (3, Spin::Full, 1600),
+        ];
+
+        for level in 1..=3 {
+            for &(lines, spin, base) in &table {
+                let mut stats = stats_at(level);
+
+                assert_eq!(points(&mut stats, lines, spin), base * level as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn difficult_clears_in_a_row_get_half_again() {
+        let mut stats = Stats::new();
+
+        assert_eq!(points(&mut stats, 4, Spin::None), 800);
+        assert!(stats.back_to_back);
+
+        assert_eq!(points(&mut stats, 2, Spin::Full), 1800);
+
+        // a lock without lines keeps the chain
+        assert_eq!(points(&mut stats, 0, Spin::None), 0);
+        assert!(stats.back_to_back);
+        assert_eq!(points(&mut stats, 1, Spin::Mini), 300);
+
+        // an easy clear breaks it
+        assert_eq!(points(&mut stats, 1, Spin::None), 100);
+        assert!(!stats.back_to_back);
+        assert_eq!(points(&mut stats, 4, Spin::None), 800);
+        assert!(stats.back_to_back);
+    }
+
+    #[test]
+    fn combo_adds_50_per_lock_in_a_row_times_the_level() {
+        let mut stats = stats_at(2);
+
+        stats.lock(1, Spin::None, false);
+        assert_eq!(stats.combo, Some(0));
+        assert_eq!(stats.score, 200);
+
+        stats.lock(1, Spin::None, false);
+        assert_eq!(stats.combo, Some(1));
+        assert_eq!(stats.score, 200 + 200 + 100);
+
+        stats.lock(1, Spin::None, false);
+        assert_eq!(stats.combo, Some(2));
+        assert_eq!(stats.score, 500 + 200 + 200);
+
+        stats.lock(0, Spin::None, false);
+        assert_eq!(stats.combo, None);
+
+        stats.lock(1, Spin::None, false);
+        assert_eq!(stats.combo, Some(0));
+        assert_eq!(stats.score, 900 + 200);
+    }
+
+    #[test]
+    fn level_goes_up_every_10_lines() {
+        let mut stats = Stats::new();
+
+        assert!(!stats.lock(4, Spin::None, false));
+        assert!(!stats.lock(4, Spin::None, false));
+        assert_eq!(stats.level, 1);
+
+        assert!(stats.lock(2, Spin::None, false));
+        assert_eq!((stats.lines, stats.level), (LINES_PER_LEVEL, 2));
+
+        assert!(!stats.lock(4, Spin::None, false));
+        assert!(!stats.lock(4, Spin::None, false));
+        assert!(stats.lock(3, Spin::None, false));
+        assert_eq!((stats.lines, stats.level), (21, 3));
+    }
+
+    #[test]
+    fn gravity_follows_the_speed_table_and_stops_at_its_end() {
+        for (index, &speed) in LEVEL_SPEEDS.iter().enumerate() {
+            let stats = stats_at(index as u32 + 1);
+
+            assert_eq!(stats.gravity(), Duration::from_millis(speed));
+        }
+
+        let last = Duration::from_millis(LEVEL_SPEEDS[LEVEL_SPEEDS.len() - 1]);
+
+        assert_eq!(stats_at(LEVEL_SPEEDS.len() as u32 + 1).gravity(), last);
+        assert_eq!(stats_at(99).gravity(), last);
+    }
+
+    #[test]
+    fn perfect_clears_add_their_bonus() {
+        let mut stats = stats_at(2);
+
+        stats.lock(4, Spin::None, true);
+        assert_eq!(stats.score, (800 + 2000) * 2);
+    }
+}
@@ -0,0 +1,9 @@
+//! Window-free core of the cylindrical tetris game.
+//!
+//! Everything in here can be linked without glfw or OpenGL, the renderer in
+//! the `tetris-3d` binary is only one consumer of it.
+
+pub mod game;
+pub use game::*;
+
+pub mod render;
@@ -1,5 +1,30 @@
 mod tetris;
 mod utils;
+
+use tetris_3d::GameLogic;
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    // tetris-3d --capture <png> [seed], renders a single frame with OpenGL in
+    // a hidden window, tetris-3d-capture does it on the cpu without a display
+    if let Some(i) = args.iter().position(|arg| arg == "--capture") {
+        let file_path = args.get(i + 1).expect("--capture needs a file path");
+
+        let seed = args
+            .get(i + 2)
+            .map_or(0, |seed| seed.parse().expect("invalid seed"));
+
+        let mut game = GameLogic::with_seed(15, 20, seed);
+
+        game.start();
+
+        let result = tetris::Tetris::new_hidden(640, 640, game).capture_frame(0.0, file_path);
+
+        result.expect("cannot save the frame");
+
+        return;
+    }
+
     tetris::Tetris::new(640, 640, "3d-tetris").run();
 }
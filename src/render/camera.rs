@@ -0,0 +1,697 @@
+use super::renderer::DEFAULT_FOV;
+use super::Frame;
+use crate::game::config::{self, ConfigError};
+use crate::{Action, GameLogic};
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::f32::consts::PI;
+use std::path::Path;
+use std::time::Duration;
+
+use nalgebra_glm as glm;
+
+/// The free camera can't look straight up or down, the view would flip.
+pub const MAX_PITCH: f32 = PI * 0.47;
+
+/// The eye and the point on the axis the orbit camera looks at are this high.
+const ORBIT_HEIGHT: f32 = 3.0;
+const DEFAULT_ORBIT_DISTANCE: f32 = 6.0;
+const MIN_ORBIT_DISTANCE: f32 = 4.0;
+const MAX_ORBIT_DISTANCE: f32 = 12.0;
+/// How far the orbit can be turned below and above the height it looks at,
+/// it stays above the floor even when zoomed out.
+const MIN_ORBIT_PITCH: f32 = -PI * 0.05;
+const MAX_ORBIT_PITCH: f32 = PI * 0.4;
+/// How fast the orbit catches up with the piece, the gap shrinks to 1/e in
+/// 1/`ORBIT_FOLLOW_RATE` seconds.
+const ORBIT_FOLLOW_RATE: f32 = 8.0;
+
+const DEFAULT_TRANSITION: Duration = Duration::from_millis(600);
+
+/// Where a free camera is and where it looks. At a yaw of 0 it looks down
+/// +x, at -90 degrees down -z towards the cylinder. Up is a positive pitch.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    pub pos: glm::Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Pose {
+    pub fn direction(&self) -> glm::Vec3 {
+        let pitch_cos = self.pitch.cos();
+
+        glm::vec3(
+            self.yaw.cos() * pitch_cos,
+            self.pitch.sin(),
+            self.yaw.sin() * pitch_cos,
+        )
+    }
+
+    fn orientation(&self) -> glm::Qua<f32> {
+        glm::quat_angle_axis(-self.yaw, &glm::vec3(0.0, 1.0, 0.0))
+            * glm::quat_angle_axis(self.pitch, &glm::vec3(0.0, 0.0, 1.0))
+    }
+
+    /// The pose `t` of the way to `other`, the position goes in a straight
+    /// line and the view turns the short way around.
+    pub fn interpolate(&self, other: &Pose, t: f32) -> Pose {
+        let pos = glm::lerp(&self.pos, &other.pos, t);
+
+        let (from, to) = (self.orientation(), other.orientation());
+
+        // half a turn apart there is no short way, the angles are turned
+        // one by one instead
+        if glm::quat_dot(&from, &to).abs() < 1e-3 {
+            return Pose {
+                pos,
+                yaw: self.yaw + (other.yaw - self.yaw) * t,
+                pitch: self.pitch + (other.pitch - self.pitch) * t,
+            };
+        }
+
+        let direction =
+            glm::quat_rotate_vec3(&glm::quat_slerp(&from, &to, t), &glm::vec3(1.0, 0.0, 0.0));
+
+        Pose {
+            pos,
+            yaw: direction.z.atan2(direction.x),
+            pitch: direction.y.clamp(-1.0, 1.0).asin(),
+        }
+    }
+}
+
+/// An eased move from one pose to another.
+#[derive(Clone, Copy, Debug)]
+pub struct Transition {
+    from: Pose,
+    to: Pose,
+    duration: Duration,
+    elapsed: Duration,
+}
+
+impl Transition {
+    pub fn new(from: Pose, to: Pose, duration: Duration) -> Self {
+        Transition {
+            from,
+            to,
+            duration,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Moves the transition `dt` forward and returns the pose there.
+    pub fn advance(&mut self, dt: Duration) -> Pose {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+
+        if self.is_finished() {
+            return self.to;
+        }
+
+        let t = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+
+        // smoothstep, it starts and stops slowly
+        self.from.interpolate(&self.to, t * t * (3.0 - 2.0 * t))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CameraPreset {
+    pub name: String,
+    pub pose: Pose,
+}
+
+impl CameraPreset {
+    /// `yaw` and `pitch` in degrees, like in the presets file.
+    pub fn new(name: &str, pos: glm::Vec3, yaw: f32, pitch: f32) -> Self {
+        CameraPreset {
+            name: name.to_string(),
+            pose: Pose {
+                pos,
+                yaw: yaw.to_radians(),
+                pitch: pitch.to_radians(),
+            },
+        }
+    }
+}
+
+/// How the camera moves and the views it can jump to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CameraSettings {
+    /// Top speed of the free camera and of zooming the orbit, units per
+    /// second.
+    pub speed: f32,
+    /// How quickly the top speed is reached, per second. The gap to it
+    /// shrinks to 1/e in 1/`acceleration` seconds.
+    pub acceleration: f32,
+    /// How quickly the camera stops once the keys are let go, per second.
+    pub damping: f32,
+    /// Radians per pixel of mouse movement.
+    pub mouse_sensitivity: f32,
+    /// Moving the mouse or the stick up looks down.
+    pub invert_y: bool,
+    /// Units per step of the scroll wheel.
+    pub zoom_step: f32,
+
+    /// The views in the order they are cycled through.
+    pub presets: Vec<CameraPreset>,
+    /// How long moving to a preset takes.
+    pub transition: Duration,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        CameraSettings {
+            speed: 3.0,
+            acceleration: 12.0,
+            damping: 8.0,
+            mouse_sensitivity: 0.005,
+            invert_y: false,
+            zoom_step: 0.5,
+
+            presets: vec![
+                CameraPreset::new("front", glm::vec3(0.0, 3.0, 6.0), -90.0, 0.0),
+                CameraPreset::new("top_down", glm::vec3(0.0, 9.0, 0.7), -90.0, -84.0),
+                CameraPreset::new("low_angle", glm::vec3(0.0, 0.5, 5.0), -90.0, 20.0),
+                CameraPreset::new("inside", glm::vec3(0.0, 0.2, 0.0), -90.0, 80.0),
+            ],
+            transition: DEFAULT_TRANSITION,
+        }
+    }
+}
+
+impl CameraSettings {
+    /// Reads the settings from a toml file, a missing file gives the defaults.
+    pub fn load(file_path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        config::load_table(file_path)?.map_or(Ok(Self::default()), Self::from_table)
+    }
+
+    /// `transition` is in milliseconds. Each `[[presets]]` table has a `name`,
+    /// a `position` and a `yaw` and `pitch` in degrees, listing any replaces
+    /// the default presets. The settings left out keep their defaults.
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        Self::from_table(config::parse_table(source)?)
+    }
+
+    fn from_table(table: toml::Table) -> Result<Self, ConfigError> {
+        let mut settings = CameraSettings::default();
+
+        for (name, value) in table {
+            let positive = || {
+                number(&value)
+                    .filter(|value| *value > 0.0)
+                    .ok_or_else(|| ConfigError::InvalidValue(name.clone()))
+            };
+
+            match name.as_str() {
+                "speed" => settings.speed = positive()?,
+                "acceleration" => settings.acceleration = positive()?,
+                "damping" => settings.damping = positive()?,
+                "mouse_sensitivity" => settings.mouse_sensitivity = positive()?,
+                "zoom_step" => settings.zoom_step = positive()?,
+                "invert_y" => {
+                    settings.invert_y = value
+                        .as_bool()
+                        .ok_or_else(|| ConfigError::InvalidValue(name.clone()))?;
+                }
+                "transition" => {
+                    let millis = value
+                        .as_integer()
+                        .and_then(|millis| u64::try_from(millis).ok())
+                        .ok_or_else(|| ConfigError::InvalidValue(name.clone()))?;
+
+                    settings.transition = Duration::from_millis(millis);
+                }
+                "presets" => {
+                    settings.presets = value
+                        .as_array()
+                        .filter(|tables| !tables.is_empty())
+                        .ok_or_else(|| ConfigError::InvalidValue(name.clone()))?
+                        .iter()
+                        .map(preset)
+                        .collect::<Result<_, _>>()?;
+                }
+                _ => return Err(ConfigError::UnknownSetting(name)),
+            }
+        }
+
+        Ok(settings)
+    }
+}
+
+fn preset(value: &toml::Value) -> Result<CameraPreset, ConfigError> {
+    let table = value
+        .as_table()
+        .ok_or_else(|| ConfigError::InvalidValue("presets".to_string()))?;
+
+    let name = table
+        .get("name")
+        .and_then(|name| name.as_str())
+        .ok_or_else(|| ConfigError::InvalidValue("presets.name".to_string()))?;
+
+    let invalid = |setting: &str| ConfigError::InvalidValue(format!("{}.{}", name, setting));
+
+    let mut pos = None;
+    let (mut yaw, mut pitch) = (0.0, 0.0);
+
+    for (setting, value) in table {
+        match setting.as_str() {
+            "name" => {}
+            "position" => {
+                let coords = value
+                    .as_array()
+                    .filter(|coords| coords.len() == 3)
+                    .ok_or_else(|| invalid(setting))?
+                    .iter()
+                    .map(|coord| number(coord).ok_or_else(|| invalid(setting)))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                pos = Some(glm::vec3(coords[0], coords[1], coords[2]));
+            }
+            "yaw" => yaw = number(value).ok_or_else(|| invalid(setting))?,
+            "pitch" => {
+                pitch = number(value)
+                    .filter(|pitch| pitch.to_radians().abs() <= MAX_PITCH)
+                    .ok_or_else(|| invalid(setting))?;
+            }
+            _ => return Err(ConfigError::UnknownSetting(setting.clone())),
+        }
+    }
+
+    let pos = pos.ok_or_else(|| invalid("position"))?;
+
+    Ok(CameraPreset::new(name, pos, yaw, pitch))
+}
+
+/// Floats and integers, so `yaw = -90` works.
+fn number(value: &toml::Value) -> Option<f32> {
+    value
+        .as_float()
+        .or_else(|| value.as_integer().map(|value| value as f64))
+        .map(|value| value as f32)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraMode {
+    /// Goes around the cylinder to keep the current piece in front, the
+    /// forward and back keys and the scroll wheel zoom. Looking around turns
+    /// it around the cylinder and up and down.
+    Orbit,
+    /// Flies with the camera keys and looks around with the mouse, the
+    /// cylinder turns under it instead.
+    FreeFly,
+}
+
+/// The camera of the window front-end. All of its input comes in through
+/// its methods, so it works the same without a window.
+pub struct Camera {
+    settings: CameraSettings,
+    mode: CameraMode,
+
+    pos: glm::Vec3,
+    yaw: f32,
+    pitch: f32,
+    /// Of the free camera, in units per second.
+    velocity: glm::Vec3,
+
+    sector_angle: f32,
+    /// The column the orbit camera is in front of.
+    orbit_column: f32,
+    /// Columns the orbit is turned away from the piece, it follows the piece
+    /// at this offset.
+    orbit_offset: f32,
+    /// Angle of the orbit above the height it looks at.
+    orbit_pitch: f32,
+    orbit_distance: f32,
+
+    /// The last preset moved to, `None` after a reset.
+    preset_index: Option<usize>,
+    /// Input is ignored while the camera moves on its own.
+    transition: Option<Transition>,
+    /// Set when the transition takes the camera back to orbiting.
+    orbit_after_transition: bool,
+
+    view: glm::Mat4,
+
+    /// `None` until the first mouse position after the cursor got captured.
+    last_mouse_pos: Option<glm::Vec2>,
+}
+
+impl Camera {
+    /// An orbit camera in front of `column`, the columns are `sector_angle`
+    /// apart.
+    pub fn new(settings: CameraSettings, sector_angle: f32, column: f32) -> Self {
+        let start = Self::start_pose();
+
+        let mut camera = Camera {
+            settings,
+            mode: CameraMode::Orbit,
+
+            pos: start.pos,
+            yaw: start.yaw,
+            pitch: start.pitch,
+            velocity: glm::vec3(0.0, 0.0, 0.0),
+
+            sector_angle,
+            orbit_column: column,
+            orbit_offset: 0.0,
+            orbit_pitch: 0.0,
+            orbit_distance: DEFAULT_ORBIT_DISTANCE,
+
+            preset_index: None,
+            transition: None,
+            orbit_after_transition: false,
+
+            view: glm::identity(),
+
+            last_mouse_pos: None,
+        };
+
+        camera.update_view();
+        camera
+    }
+
+    /// The orbit camera at its starting distance, seen from the free camera.
+    fn start_pose() -> Pose {
+        Pose {
+            pos: glm::vec3(0.0, ORBIT_HEIGHT, DEFAULT_ORBIT_DISTANCE),
+            yaw: -PI * 0.5,
+            pitch: 0.0,
+        }
+    }
+
+    pub fn settings(&self) -> &CameraSettings {
+        &self.settings
+    }
+
+    pub fn view(&self) -> glm::Mat4 {
+        self.view
+    }
+
+    /// What this camera sees of `game`, with the default field of view.
+    pub fn frame(&self, game: &GameLogic, size: (u32, u32), time: f32) -> Frame {
+        let (column_offset, front_column) = match self.mode {
+            CameraMode::Orbit => (0.0, self.orbit_column),
+            // the cylinder turns under the free camera to bring the piece
+            // to the front
+            CameraMode::FreeFly => {
+                let column = game.current_piece().draw_pos.x;
+
+                (column, column)
+            }
+        };
+
+        Frame {
+            view: self.view,
+            eye: self.pos(),
+            size,
+            fov: DEFAULT_FOV,
+            time,
+            column_offset,
+            front_column,
+        }
+    }
+
+    pub fn mode(&self) -> CameraMode {
+        self.mode
+    }
+
+    pub fn orbit_column(&self) -> f32 {
+        self.orbit_column
+    }
+
+    pub fn pos(&self) -> glm::Vec3 {
+        match self.mode {
+            CameraMode::Orbit => {
+                // column 0 faces +z, the same as the cylinder turned by 0
+                let angle = self.orbit_column * self.sector_angle;
+                let radius = self.orbit_pitch.cos() * self.orbit_distance;
+
+                glm::vec3(
+                    angle.sin() * radius,
+                    ORBIT_HEIGHT + self.orbit_pitch.sin() * self.orbit_distance,
+                    angle.cos() * radius,
+                )
+            }
+            CameraMode::FreeFly => self.pos,
+        }
+    }
+
+    /// Switches between orbiting and flying, each mode keeps where it was.
+    pub fn toggle_mode(&mut self) {
+        if self.transition.is_some() {
+            return;
+        }
+
+        self.mode = match self.mode {
+            CameraMode::Orbit => CameraMode::FreeFly,
+            CameraMode::FreeFly => CameraMode::Orbit,
+        };
+
+        self.velocity = glm::vec3(0.0, 0.0, 0.0);
+
+        self.update_view();
+    }
+
+    /// Moves back to orbiting in front of the piece at the starting distance.
+    pub fn reset(&mut self) {
+        self.move_to(Self::start_pose());
+
+        self.preset_index = None;
+        self.orbit_after_transition = true;
+    }
+
+    /// Moves to the preset after the last one, it flies there. Does nothing
+    /// without presets.
+    pub fn next_preset(&mut self) {
+        if self.settings.presets.is_empty() {
+            return;
+        }
+
+        let index = self
+            .preset_index
+            .map_or(0, |index| (index + 1) % self.settings.presets.len());
+
+        self.move_to(self.settings.presets[index].pose);
+
+        self.preset_index = Some(index);
+    }
+
+    /// Starts a transition from where the camera is, seen from the free
+    /// camera, to `pose`.
+    fn move_to(&mut self, pose: Pose) {
+        let from = match self.mode {
+            // the orbit is in front of the piece, where the free camera has
+            // the piece too
+            CameraMode::Orbit => Pose {
+                pos: glm::vec3(
+                    0.0,
+                    ORBIT_HEIGHT + self.orbit_pitch.sin() * self.orbit_distance,
+                    self.orbit_pitch.cos() * self.orbit_distance,
+                ),
+                pitch: -self.orbit_pitch,
+                ..Self::start_pose()
+            },
+            CameraMode::FreeFly => self.pose(),
+        };
+
+        self.mode = CameraMode::FreeFly;
+        self.velocity = glm::vec3(0.0, 0.0, 0.0);
+        self.set_pose(from);
+
+        self.transition = Some(Transition::new(from, pose, self.settings.transition));
+        self.orbit_after_transition = false;
+    }
+
+    /// Where the free camera is.
+    pub fn pose(&self) -> Pose {
+        Pose {
+            pos: self.pos,
+            yaw: self.yaw,
+            pitch: self.pitch,
+        }
+    }
+
+    fn set_pose(&mut self, pose: Pose) {
+        self.pos = pose.pos;
+        self.yaw = pose.yaw;
+        self.pitch = pose.pitch;
+
+        self.update_view();
+    }
+
+    fn update_view(&mut self) {
+        let up = glm::vec3(0.0, 1.0, 0.0);
+
+        self.view = match self.mode {
+            CameraMode::Orbit => glm::look_at(&self.pos(), &glm::vec3(0.0, ORBIT_HEIGHT, 0.0), &up),
+            CameraMode::FreeFly => {
+                glm::look_at(&self.pos, &(self.pos + self.pose().direction()), &up)
+            }
+        };
+    }
+
+    /// Moves the camera `dt` forward with the camera actions that are `held`
+    /// down. The orbit eases towards `column`, the column of the current
+    /// piece. The columns aren't wrapped, so it always takes the short way
+    /// around.
+    pub fn update(&mut self, held: &HashSet<Action>, column: f32, dt: Duration) {
+        if let Some(transition) = &mut self.transition {
+            let pose = transition.advance(dt);
+            let finished = transition.is_finished();
+
+            self.set_pose(pose);
+
+            if finished {
+                self.transition = None;
+
+                if self.orbit_after_transition {
+                    self.mode = CameraMode::Orbit;
+                    self.orbit_column = column;
+                    self.orbit_offset = 0.0;
+                    self.orbit_pitch = 0.0;
+                    self.orbit_distance = DEFAULT_ORBIT_DISTANCE;
+
+                    self.update_view();
+                }
+            }
+
+            return;
+        }
+
+        match self.mode {
+            CameraMode::Orbit => {
+                let t = 1.0 - (-ORBIT_FOLLOW_RATE * dt.as_secs_f32()).exp();
+
+                self.orbit_column += (column + self.orbit_offset - self.orbit_column) * t;
+
+                let zoom = match (
+                    held.contains(&Action::CameraForward),
+                    held.contains(&Action::CameraBack),
+                ) {
+                    (true, false) => -1.0,
+                    (false, true) => 1.0,
+                    _ => 0.0,
+                };
+
+                self.zoom(zoom * self.settings.speed * dt.as_secs_f32());
+            }
+            CameraMode::FreeFly => self.fly(held, dt),
+        }
+
+        self.update_view();
+    }
+
+    fn fly(&mut self, held: &HashSet<Action>, dt: Duration) {
+        let direction = self.pose().direction();
+        let right = glm::normalize(&glm::cross(&direction, &glm::vec3(0.0, 1.0, 0.0)));
+
+        let wish = [
+            (Action::CameraForward, direction),
+            (Action::CameraBack, -direction),
+            (Action::CameraRight, right),
+            (Action::CameraLeft, -right),
+        ]
+        .iter()
+        .filter(|(action, _)| held.contains(action))
+        .fold(glm::vec3(0.0, 0.0, 0.0), |wish, (_, vec)| wish + vec);
+
+        let (target, rate) = if wish == glm::vec3(0.0, 0.0, 0.0) {
+            (wish, self.settings.damping)
+        } else {
+            (
+                glm::normalize(&wish) * self.settings.speed,
+                self.settings.acceleration,
+            )
+        };
+
+        // the velocity closes in on the target exponentially, the position
+        // follows its integral so it doesn't depend on how dt is split
+        let dt = dt.as_secs_f32();
+        let decay = (-rate * dt).exp();
+
+        self.pos += target * dt + (self.velocity - target) * ((1.0 - decay) / rate);
+        self.velocity = target + (self.velocity - target) * decay;
+    }
+
+    /// Moves the orbit `amount` units away from the cylinder, within limits.
+    fn zoom(&mut self, amount: f32) {
+        self.orbit_distance =
+            (self.orbit_distance + amount).clamp(MIN_ORBIT_DISTANCE, MAX_ORBIT_DISTANCE);
+    }
+
+    /// Zooms by `steps` of the scroll wheel, positive steps go closer. The
+    /// free camera moves along where it looks.
+    pub fn scroll(&mut self, steps: f32) {
+        if self.transition.is_some() {
+            return;
+        }
+
+        let amount = steps * self.settings.zoom_step;
+
+        match self.mode {
+            CameraMode::Orbit => self.zoom(-amount),
+            CameraMode::FreeFly => self.pos += self.pose().direction() * amount,
+        }
+
+        self.update_view();
+    }
+
+    /// Looks around with the mouse at `pos` in pixels, from the second
+    /// position after `forget_mouse`.
+    pub fn look_at(&mut self, pos: glm::Vec2) {
+        if let Some(last) = self.last_mouse_pos.replace(pos) {
+            let offset = pos - last;
+
+            // the pixel rows go down
+            self.turn(glm::vec2(offset.x, -offset.y) * self.settings.mouse_sensitivity);
+        }
+    }
+
+    /// The cursor left mouse look, it may come back anywhere.
+    pub fn forget_mouse(&mut self) {
+        self.last_mouse_pos = None;
+    }
+
+    /// Turns the view by `offset` radians of yaw and pitch, up is positive
+    /// unless the y axis is inverted. The orbit camera keeps looking at the
+    /// cylinder and moves around it instead, turning right takes it left.
+    pub fn turn(&mut self, offset: glm::Vec2) {
+        if self.transition.is_some() {
+            return;
+        }
+
+        let pitch = if self.settings.invert_y {
+            -offset.y
+        } else {
+            offset.y
+        };
+
+        match self.mode {
+            CameraMode::Orbit => {
+                let columns = offset.x / self.sector_angle;
+
+                self.orbit_offset -= columns;
+                self.orbit_column -= columns;
+                self.orbit_pitch =
+                    (self.orbit_pitch - pitch).clamp(MIN_ORBIT_PITCH, MAX_ORBIT_PITCH);
+
+                self.update_view();
+            }
+            CameraMode::FreeFly => {
+                let pose = Pose {
+                    pos: self.pos,
+                    yaw: self.yaw + offset.x,
+                    pitch: (self.pitch + pitch).clamp(-MAX_PITCH, MAX_PITCH),
+                };
+
+                self.set_pose(pose);
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use super::{Camera, CameraSettings, DisplaySettings, Frame, Light, Renderer, SoftwareRenderer};
+use crate::game::GameLogic;
+
+use std::path::Path;
+
+/// Renders `game` on the cpu from the orbit camera in front of its piece and
+/// saves it as a png, it needs neither a display nor OpenGL.
+pub fn capture(
+    game: &GameLogic,
+    settings: CameraSettings,
+    size: (u32, u32),
+    time: f32,
+    file_path: impl AsRef<Path>,
+) -> image::ImageResult<()> {
+    let camera = Camera::new(
+        settings,
+        super::scene::sector_angle(game),
+        game.current_piece().pos.x as f32,
+    );
+
+    let frame = Frame {
+        fov: DisplaySettings::default().fov_radians(),
+        ..camera.frame(game, size, time)
+    };
+
+    let mut renderer = SoftwareRenderer::new(game, Light::default());
+
+    renderer.draw(game, &frame);
+
+    let image = image::RgbaImage::from_raw(size.0, size.1, renderer.pixels().to_vec())
+        .expect("pixel buffer has the size of the frame");
+
+    image.save(file_path)
+}
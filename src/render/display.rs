@@ -0,0 +1,115 @@
+use crate::game::config::{self, ConfigError};
+
+use std::path::Path;
+
+/// The narrowest and widest vertical field of view, in degrees.
+pub const MIN_FOV: f32 = 20.0;
+pub const MAX_FOV: f32 = 120.0;
+
+/// How the window fills the screen. The window front-end keeps the changes
+/// made while playing in a file of the user, over the shipped defaults.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplaySettings {
+    /// Vertical field of view in degrees.
+    pub fov: f32,
+    pub fullscreen: bool,
+    /// Fullscreen covers the monitor with a window without borders instead
+    /// of switching its video mode, alt-tabbing out is instant.
+    pub borderless: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        DisplaySettings {
+            fov: 60.0,
+            fullscreen: false,
+            borderless: true,
+        }
+    }
+}
+
+impl DisplaySettings {
+    /// Reads the settings from a toml file, a missing file gives the defaults.
+    pub fn load(file_path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::default().load_over(file_path)
+    }
+
+    /// These settings with the ones in a toml file on top, a missing file
+    /// changes nothing. The user's own file goes over the shipped one.
+    pub fn load_over(self, file_path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        config::load_table(file_path)?.map_or(Ok(self), |table| self.with_table(table))
+    }
+
+    /// `fov` is in degrees, between `MIN_FOV` and `MAX_FOV`. The settings
+    /// left out keep their defaults.
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        Self::default().with_table(config::parse_table(source)?)
+    }
+
+    fn with_table(mut self, table: toml::Table) -> Result<Self, ConfigError> {
+        for (name, value) in table {
+            let flag = || {
+                value
+                    .as_bool()
+                    .ok_or_else(|| ConfigError::InvalidValue(name.clone()))
+            };
+
+            match name.as_str() {
+                "fov" => {
+                    self.fov = value
+                        .as_float()
+                        .or_else(|| value.as_integer().map(|value| value as f64))
+                        .map(|value| value as f32)
+                        .filter(|value| (MIN_FOV..=MAX_FOV).contains(value))
+                        .ok_or_else(|| ConfigError::InvalidValue(name.clone()))?;
+                }
+                "fullscreen" => self.fullscreen = flag()?,
+                "borderless" => self.borderless = flag()?,
+                _ => return Err(ConfigError::UnknownSetting(name)),
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// The settings that differ from `base`, as a toml file `load_over`
+    /// reads back on top of it.
+    pub fn overrides_of(&self, base: &DisplaySettings) -> String {
+        let mut table = toml::Table::new();
+
+        if self.fov != base.fov {
+            table.insert("fov".to_string(), f64::from(self.fov).into());
+        }
+
+        if self.fullscreen != base.fullscreen {
+            table.insert("fullscreen".to_string(), self.fullscreen.into());
+        }
+
+        if self.borderless != base.borderless {
+            table.insert("borderless".to_string(), self.borderless.into());
+        }
+
+        table.to_string()
+    }
+
+    /// Writes the settings that differ from `base` to `file_path`, creating
+    /// its directory.
+    pub fn save_over(
+        &self,
+        base: &DisplaySettings,
+        file_path: impl AsRef<Path>,
+    ) -> Result<(), ConfigError> {
+        let file_path = file_path.as_ref();
+
+        if let Some(dir) = file_path.parent() {
+            std::fs::create_dir_all(dir).map_err(ConfigError::Io)?;
+        }
+
+        std::fs::write(file_path, self.overrides_of(base)).map_err(ConfigError::Io)
+    }
+
+    /// The field of view for `Frame::fov`.
+    pub fn fov_radians(&self) -> f32 {
+        self.fov.to_radians()
+    }
+}
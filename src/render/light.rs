@@ -0,0 +1,61 @@
+use nalgebra_glm as glm;
+
+/// A directional light with an ambient term.
+#[derive(Clone, Copy, Debug)]
+pub struct Light {
+    /// The direction the light travels in, it doesn't need to be normalized.
+    pub direction: glm::Vec3,
+    pub color: glm::Vec3,
+
+    /// Light every face gets, even the ones turned away.
+    pub ambient: f32,
+    pub specular: f32,
+    pub shininess: f32,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Light {
+            direction: glm::vec3(-0.4, -1.0, -0.6),
+            color: glm::vec3(1.0, 1.0, 1.0),
+
+            ambient: 0.35,
+            specular: 0.3,
+            shininess: 32.0,
+        }
+    }
+}
+
+impl Light {
+    /// Blinn-Phong shading of `color` on a surface at `pos` facing `normal`,
+    /// seen from `eye`. `lit` is how much of the light reaches the surface.
+    pub fn shade(
+        &self,
+        color: glm::Vec4,
+        pos: &glm::Vec3,
+        normal: &glm::Vec3,
+        eye: &glm::Vec3,
+        lit: f32,
+    ) -> glm::Vec4 {
+        let normal = glm::normalize(normal);
+        let to_light = glm::normalize(&-self.direction);
+        let to_view = glm::normalize(&(eye - pos));
+
+        let diffuse = glm::dot(&normal, &to_light).max(0.0);
+
+        // faces turned away from the light get no highlight
+        let specular = if diffuse > 0.0 {
+            let halfway = glm::normalize(&(to_light + to_view));
+
+            glm::dot(&normal, &halfway).max(0.0).powf(self.shininess) * self.specular
+        } else {
+            0.0
+        };
+
+        let rgb = color.xyz().component_mul(
+            &(glm::vec3(self.ambient, self.ambient, self.ambient) + self.color * lit * diffuse),
+        ) + self.color * lit * specular;
+
+        glm::vec4(rgb.x, rgb.y, rgb.z, color.w)
+    }
+}
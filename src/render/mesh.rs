@@ -0,0 +1,77 @@
+use nalgebra_glm as glm;
+
+/// Triangles as (position, normal) pairs and indices into them, every face
+/// has its own vertices so the normals stay flat.
+pub struct Mesh {
+    pub vertices: Vec<[glm::Vec3; 2]>,
+    pub indices: Vec<u32>,
+}
+
+impl Mesh {
+    /// A single block of the cylinder, a ring slice `sector_angle` wide
+    /// starting on the x axis.
+    pub fn sector(
+        sector_angle: f32,
+        sector_height: f32,
+        inner_radius: f32,
+        outer_radius: f32,
+    ) -> Self {
+        let angle_vec = glm::vec3(sector_angle.cos(), 0.0, sector_angle.sin());
+        let half_angle_vec = glm::vec3((sector_angle * 0.5).cos(), 0.0, (sector_angle * 0.5).sin());
+
+        let bottom = [
+            glm::vec3(outer_radius, 0.0, 0.0),
+            angle_vec * outer_radius,
+            angle_vec * inner_radius,
+            glm::vec3(inner_radius, 0.0, 0.0),
+        ];
+
+        let top = bottom.map(|mut vec| {
+            vec.y = sector_height;
+            vec
+        });
+
+        Self::from_quads(&[
+            (bottom, glm::vec3(0.0, -1.0, 0.0)),
+            (top, glm::vec3(0.0, 1.0, 0.0)),
+            ([bottom[0], bottom[1], top[1], top[0]], half_angle_vec),
+            ([bottom[3], bottom[2], top[2], top[3]], -half_angle_vec),
+            (
+                [bottom[0], bottom[3], top[3], top[0]],
+                glm::vec3(0.0, 0.0, -1.0),
+            ),
+            (
+                [bottom[1], bottom[2], top[2], top[1]],
+                glm::vec3(-sector_angle.sin(), 0.0, sector_angle.cos()),
+            ),
+        ])
+    }
+
+    /// A flat square around the origin facing up, `size` is half of its width.
+    pub fn floor(size: f32) -> Self {
+        Self::from_quads(&[(
+            [
+                glm::vec3(-size, 0.0, -size),
+                glm::vec3(size, 0.0, -size),
+                glm::vec3(size, 0.0, size),
+                glm::vec3(-size, 0.0, size),
+            ],
+            glm::vec3(0.0, 1.0, 0.0),
+        )])
+    }
+
+    /// Two triangles for every quad, the corners go in order around it.
+    fn from_quads(quads: &[([glm::Vec3; 4], glm::Vec3)]) -> Self {
+        let mut vertices = Vec::with_capacity(quads.len() * 4);
+        let mut indices = Vec::with_capacity(quads.len() * 6);
+
+        for (corners, normal) in quads.iter() {
+            let base = vertices.len() as u32;
+
+            vertices.extend(corners.iter().map(|corner| [*corner, *normal]));
+            indices.extend([0, 1, 2, 0, 2, 3].iter().map(|i| base + i));
+        }
+
+        Mesh { vertices, indices }
+    }
+}
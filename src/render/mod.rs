@@ -0,0 +1,28 @@
+pub mod light;
+pub use light::Light;
+
+pub mod mesh;
+pub use mesh::Mesh;
+
+pub mod renderer;
+pub use renderer::{Frame, Renderer};
+
+pub mod scene;
+pub use scene::Sector;
+
+pub mod software;
+pub use software::SoftwareRenderer;
+
+pub mod text;
+pub use text::TextImage;
+
+pub mod display;
+pub use display::DisplaySettings;
+
+pub mod camera;
+pub use camera::{Camera, CameraMode, CameraPreset, CameraSettings, Pose, Transition};
+
+#[cfg(feature = "capture")]
+pub mod capture;
+#[cfg(feature = "capture")]
+pub use capture::capture;
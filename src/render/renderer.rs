@@ -0,0 +1,46 @@
+use super::Light;
+use crate::GameLogic;
+
+use std::f32::consts::PI;
+
+use nalgebra_glm as glm;
+
+/// The vertical field of view of `DisplaySettings::default`, 60 degrees.
+pub const DEFAULT_FOV: f32 = PI / 3.0;
+
+/// Everything about a frame that doesn't come from the game.
+#[derive(Clone, Copy, Debug)]
+pub struct Frame {
+    pub view: glm::Mat4,
+    /// Position of the camera, for the highlights.
+    pub eye: glm::Vec3,
+    pub size: (u32, u32),
+    /// Vertical field of view in radians.
+    pub fov: f32,
+    /// In seconds, only moves the preview ring.
+    pub time: f32,
+    /// Turns the cylinder so this column faces the +z axis, for cameras that
+    /// don't move around it.
+    pub column_offset: f32,
+    /// The column in front of the camera, the held piece and the preview ring
+    /// are placed around it.
+    pub front_column: f32,
+}
+
+impl Frame {
+    /// The aspect ratio follows `size`, so resizing doesn't stretch the
+    /// scene. A minimized window has no height, it is kept at one pixel.
+    pub fn view_projection(&self) -> glm::Mat4 {
+        let aspect_ratio = (self.size.0.max(1) as f32) / (self.size.1.max(1) as f32);
+
+        glm::perspective(aspect_ratio, self.fov, 0.1, 100.0) * self.view
+    }
+}
+
+/// A backend that draws the cylinder, all of them lay out the scene with
+/// `scene` so they show the same picture.
+pub trait Renderer {
+    fn set_light(&mut self, light: Light);
+
+    fn draw(&mut self, game: &GameLogic, frame: &Frame);
+}
@@ -0,0 +1,166 @@
+use super::Frame;
+use crate::{GameLogic, GameState};
+
+use std::cell::RefCell;
+use std::f32::consts::PI;
+
+use nalgebra_glm as glm;
+
+pub const BLOCK_SCALE: f32 = 0.2;
+pub const PREVIEW_SCALE: f32 = 0.1;
+/// Columns per second the preview ring turns by.
+pub const PREVIEW_ORBIT_SPEED: f32 = 0.5;
+
+pub const SECTOR_HEIGHT: f32 = 1.0;
+pub const INNER_RADIUS: f32 = 2.7;
+pub const OUTER_RADIUS: f32 = 3.0;
+
+/// Half of the width of the floor in world units.
+pub const FLOOR_SIZE: f32 = 2.0;
+
+pub const SKY_COLOR: [f32; 3] = [135.0 / 255.0, 206.0 / 255.0, 235.0 / 255.0];
+
+/// One drawn block, of the sector mesh or the floor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sector {
+    /// In columns, the mesh turns by the sector angle for each.
+    pub column: f32,
+    /// In sector heights before scaling.
+    pub height: f32,
+    pub scale: f32,
+    pub color: glm::Vec4,
+}
+
+impl Sector {
+    /// Same transform as `res/vertex.glsl`, `column_offset` turns the whole
+    /// ring back.
+    pub fn model(&self, column_offset: f32, sector_angle: f32) -> glm::Mat4 {
+        let model = glm::scale(
+            &glm::identity(),
+            &glm::vec3(self.scale, self.scale, self.scale),
+        );
+
+        let model = glm::rotate_y(
+            &model,
+            (self.column - column_offset) * sector_angle - PI * 0.5,
+        );
+
+        glm::translate(&model, &glm::vec3(0.0, self.height, 0.0))
+    }
+}
+
+pub fn sector_angle(game: &GameLogic) -> f32 {
+    2.0 * PI / (game.grid_width as f32)
+}
+
+/// The sphere the light has to cover, around the cylinder and the floor.
+pub fn bounds(game: &GameLogic) -> (glm::Vec3, f32) {
+    let cylinder_height = game.grid_height as f32 * SECTOR_HEIGHT * BLOCK_SCALE;
+
+    let radius = (cylinder_height * cylinder_height * 0.25 + FLOOR_SIZE * FLOOR_SIZE * 2.0).sqrt();
+
+    (glm::vec3(0.0, cylinder_height * 0.5, 0.0), radius)
+}
+
+/// The settled cells of the grid, they only change with `grid_revision` and
+/// during line clears.
+pub fn grid_sectors(game: &GameLogic) -> Vec<Sector> {
+    let sectors = RefCell::new(Vec::new());
+    let push = pusher(game.state(), &sectors);
+
+    game.draw_cells_with(|x, y, color| {
+        push(x as f32, block_height(game, y as f32), BLOCK_SCALE, color);
+    });
+
+    sectors.take()
+}
+
+/// The current piece, its ghost, the held piece and the preview ring of
+/// `frame`. The grid and these are drawn with the same column offset.
+pub fn moving_sectors(game: &GameLogic, frame: &Frame) -> Vec<Sector> {
+    let sectors = RefCell::new(Vec::new());
+    let push = pusher(game.state(), &sectors);
+
+    let draw_block = |x: f32, y: f32, color: Option<glm::Vec4>| {
+        push(x, block_height(game, y), BLOCK_SCALE, color);
+    };
+
+    game.draw_piece_with(draw_block);
+    game.draw_hold_with(|x, y, color| draw_block(x + frame.front_column, y, color));
+
+    // the preview pieces orbit in a smaller ring above the cylinder
+    let orbit = frame.front_column + frame.time * PREVIEW_ORBIT_SPEED;
+    let spacing = game.grid_width as f32 / game.preview_len() as f32;
+    let preview_top = (game.grid_height as f32 * SECTOR_HEIGHT * BLOCK_SCALE
+        + 2.0 * SECTOR_HEIGHT * PREVIEW_SCALE)
+        / PREVIEW_SCALE;
+
+    game.draw_preview_with(|i, v, color| {
+        push(
+            v.x + i as f32 * spacing + orbit,
+            preview_top - v.y * SECTOR_HEIGHT,
+            PREVIEW_SCALE,
+            color,
+        );
+    });
+
+    sectors.take()
+}
+
+/// The floor mesh, just below the bottom row so it doesn't fight with it.
+pub fn floor_sector(game: &GameLogic) -> Sector {
+    Sector {
+        column: 0.0,
+        height: -0.01,
+        scale: 1.0,
+        color: state_tint(game.state(), glm::vec4(0.55, 0.6, 0.5, 1.0)),
+    }
+}
+
+/// Overlay for the states where the game is frozen, dims the scene while
+/// waiting or paused and greys it out after a game over.
+pub fn state_tint(state: GameState, color: glm::Vec4) -> glm::Vec4 {
+    match state {
+        GameState::Ready | GameState::Paused => {
+            glm::vec4(color.x * 0.4, color.y * 0.4, color.z * 0.4, color.w)
+        }
+        GameState::GameOver => {
+            let grey = (color.x + color.y + color.z) / 3.0 * 0.6;
+
+            glm::vec4(grey, grey, grey, color.w)
+        }
+        _ => color,
+    }
+}
+
+/// What the keys do in the states where the game is frozen, shown over the
+/// scene.
+pub fn state_prompt(state: GameState) -> Option<&'static str> {
+    match state {
+        GameState::Ready => Some("press enter to start"),
+        GameState::Paused => Some("paused, p to resume"),
+        GameState::GameOver => Some("game over, enter to restart"),
+        _ => None,
+    }
+}
+
+fn block_height(game: &GameLogic, y: f32) -> f32 {
+    (game.grid_height as f32 - y - 1.0) * SECTOR_HEIGHT
+}
+
+/// Pushes the drawn cells into `sectors`, tinted for `state`.
+fn pusher(
+    state: GameState,
+    sectors: &RefCell<Vec<Sector>>,
+) -> impl Fn(f32, f32, f32, Option<glm::Vec4>) + '_ {
+    move |column, height, scale, color| {
+        if let Some(color) = color {
+            sectors.borrow_mut().push(Sector {
+                column,
+                height,
+                scale,
+                color: state_tint(state, color),
+            });
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use super::scene::{self, Sector};
+use super::{Frame, Light, Mesh, Renderer};
+use crate::GameLogic;
+
+use nalgebra_glm as glm;
+
+/// Clip space w below which a triangle is dropped instead of clipped.
+const NEAR_W: f32 = 0.1;
+
+/// Renders on the cpu into an rgba buffer, without shadows. The output only
+/// depends on the game and the frame, so it can be compared pixel by pixel.
+pub struct SoftwareRenderer {
+    canvas: Canvas,
+    light: Light,
+
+    sector_angle: f32,
+
+    sector_mesh: Mesh,
+    floor_mesh: Mesh,
+}
+
+impl SoftwareRenderer {
+    pub fn new(game: &GameLogic, light: Light) -> Self {
+        let sector_angle = scene::sector_angle(game);
+
+        SoftwareRenderer {
+            canvas: Canvas::new(0, 0),
+            light,
+
+            sector_angle,
+
+            sector_mesh: Mesh::sector(
+                sector_angle,
+                scene::SECTOR_HEIGHT,
+                scene::INNER_RADIUS,
+                scene::OUTER_RADIUS,
+            ),
+            floor_mesh: Mesh::floor(scene::FLOOR_SIZE),
+        }
+    }
+
+    /// Size of the last drawn frame.
+    pub fn size(&self) -> (u32, u32) {
+        (self.canvas.width, self.canvas.height)
+    }
+
+    /// The last drawn frame as rgba rows, starting from the top row.
+    pub fn pixels(&self) -> &[u8] {
+        &self.canvas.color
+    }
+
+    fn draw_sectors(
+        &mut self,
+        sectors: &[Sector],
+        floor: bool,
+        column_offset: f32,
+        view_projection: &glm::Mat4,
+        eye: &glm::Vec3,
+    ) {
+        let mesh = if floor {
+            &self.floor_mesh
+        } else {
+            &self.sector_mesh
+        };
+
+        for sector in sectors {
+            let model = sector.model(column_offset, self.sector_angle);
+            let mvp = view_projection * model;
+
+            for triangle in mesh.indices.chunks(3) {
+                let vertices = [
+                    mesh.vertices[triangle[0] as usize],
+                    mesh.vertices[triangle[1] as usize],
+                    mesh.vertices[triangle[2] as usize],
+                ];
+
+                // the faces are flat, so shading the middle is enough
+                let center = vertices
+                    .iter()
+                    .fold(glm::vec3(0.0, 0.0, 0.0), |sum, v| sum + v[0])
+                    / 3.0;
+
+                let world_pos = (model * center.push(1.0)).xyz();
+                let normal = (model * vertices[0][1].push(0.0)).xyz();
+
+                let color = self
+                    .light
+                    .shade(sector.color, &world_pos, &normal, eye, 1.0);
+
+                let clip = vertices.map(|v| mvp * v[0].push(1.0));
+
+                self.canvas.draw_triangle(clip, color);
+            }
+        }
+    }
+}
+
+impl Renderer for SoftwareRenderer {
+    fn set_light(&mut self, light: Light) {
+        self.light = light;
+    }
+
+    fn draw(&mut self, game: &GameLogic, frame: &Frame) {
+        if self.size() != frame.size {
+            self.canvas = Canvas::new(frame.size.0, frame.size.1);
+        }
+
+        self.canvas.clear(scene::SKY_COLOR);
+
+        let view_projection = frame.view_projection();
+
+        // same order as the gl renderer, the blending depends on it
+        self.draw_sectors(
+            &scene::grid_sectors(game),
+            false,
+            frame.column_offset,
+            &view_projection,
+            &frame.eye,
+        );
+        self.draw_sectors(
+            &scene::moving_sectors(game, frame),
+            false,
+            frame.column_offset,
+            &view_projection,
+            &frame.eye,
+        );
+        self.draw_sectors(
+            &[scene::floor_sector(game)],
+            true,
+            0.0,
+            &view_projection,
+            &frame.eye,
+        );
+    }
+}
+
+/// Color and depth of the pixels, rows start from the top.
+struct Canvas {
+    width: u32,
+    height: u32,
+
+    color: Vec<u8>,
+    depth: Vec<f32>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        let len = (width * height) as usize;
+
+        Canvas {
+            width,
+            height,
+
+            color: vec![0; len * 4],
+            depth: vec![1.0; len],
+        }
+    }
+
+    fn clear(&mut self, color: [f32; 3]) {
+        let pixel = [
+            to_byte(color[0]),
+            to_byte(color[1]),
+            to_byte(color[2]),
+            u8::MAX,
+        ];
+
+        for it in self.color.chunks_mut(4) {
+            it.copy_from_slice(&pixel);
+        }
+
+        self.depth.fill(1.0);
+    }
+
+    /// Fills the triangle with `color`, with a depth test and alpha blending
+    /// like the gl renderer.
+    fn draw_triangle(&mut self, clip: [glm::Vec4; 3], color: glm::Vec4) {
+        if clip.iter().any(|v| v.w < NEAR_W) {
+            return;
+        }
+
+        let (width, height) = (self.width as f32, self.height as f32);
+
+        let screen = clip.map(|v| {
+            let ndc = v.xyz() / v.w;
+
+            glm::vec3(
+                (ndc.x + 1.0) * 0.5 * width,
+                (1.0 - ndc.y) * 0.5 * height,
+                ndc.z * 0.5 + 0.5,
+            )
+        });
+
+        let area = edge(&screen[0], &screen[1], &screen[2]);
+
+        if area.abs() < f32::EPSILON {
+            return;
+        }
+
+        let min_x = screen.iter().map(|v| v.x).fold(f32::MAX, f32::min).max(0.0) as u32;
+        let min_y = screen.iter().map(|v| v.y).fold(f32::MAX, f32::min).max(0.0) as u32;
+        let max_x = screen
+            .iter()
+            .map(|v| v.x)
+            .fold(f32::MIN, f32::max)
+            .min(width - 1.0);
+        let max_y = screen
+            .iter()
+            .map(|v| v.y)
+            .fold(f32::MIN, f32::max)
+            .min(height - 1.0);
+
+        if max_x < 0.0 || max_y < 0.0 {
+            return;
+        }
+
+        for y in min_y..=max_y as u32 {
+            for x in min_x..=max_x as u32 {
+                let p = glm::vec3(x as f32 + 0.5, y as f32 + 0.5, 0.0);
+
+                // barycentric weights, positive inside for either winding
+                let w0 = edge(&screen[1], &screen[2], &p) / area;
+                let w1 = edge(&screen[2], &screen[0], &p) / area;
+                let w2 = edge(&screen[0], &screen[1], &p) / area;
+
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+
+                let z = w0 * screen[0].z + w1 * screen[1].z + w2 * screen[2].z;
+
+                let index = (y * self.width + x) as usize;
+
+                if !(0.0..=1.0).contains(&z) || z >= self.depth[index] {
+                    continue;
+                }
+
+                self.depth[index] = z;
+
+                let dst = &mut self.color[index * 4..index * 4 + 4];
+
+                for (i, src) in color.iter().enumerate() {
+                    let blended = src * color.w + (dst[i] as f32 / 255.0) * (1.0 - color.w);
+
+                    dst[i] = to_byte(blended);
+                }
+            }
+        }
+    }
+}
+
+fn edge(a: &glm::Vec3, b: &glm::Vec3, p: &glm::Vec3) -> f32 {
+    (p.x - a.x) * (b.y - a.y) - (p.y - a.y) * (b.x - a.x)
+}
+
+fn to_byte(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
@@ -0,0 +1,115 @@
+//! A tiny bitmap font for the few words the front-ends show over the scene,
+//! so they don't need a font file or a text library.
+
+/// Size of a glyph in font pixels, the glyphs are one pixel apart.
+pub const GLYPH_WIDTH: u32 = 5;
+pub const GLYPH_HEIGHT: u32 = 7;
+
+/// Font pixels between the text and the edge of its box.
+const PADDING: u32 = 2;
+
+const TEXT_COLOR: [u8; 4] = [255, 255, 255, 255];
+const BOX_COLOR: [u8; 4] = [0, 0, 0, 160];
+
+/// Rows from the top, the leftmost pixel is bit 4.
+type Glyph = [u8; GLYPH_HEIGHT as usize];
+
+const LETTERS: [Glyph; 26] = [
+    [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // a
+    [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e], // b
+    [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e], // c
+    [0x1e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1e], // d
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f], // e
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10], // f
+    [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f], // g
+    [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11], // h
+    [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e], // i
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c], // j
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // k
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f], // l
+    [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11], // m
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // n
+    [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // o
+    [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10], // p
+    [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d], // q
+    [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11], // r
+    [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e], // s
+    [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // t
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e], // u
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04], // v
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a], // w
+    [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11], // x
+    [0x11, 0x11, 0x0a, 0x04, 0x04, 0x04, 0x04], // y
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f], // z
+];
+
+const DIGITS: [Glyph; 10] = [
+    [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e], // 0
+    [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e], // 1
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f], // 2
+    [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e], // 3
+    [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02], // 4
+    [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e], // 5
+    [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e], // 6
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08], // 7
+    [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e], // 8
+    [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c], // 9
+];
+
+const COMMA: Glyph = [0x00, 0x00, 0x00, 0x00, 0x0c, 0x04, 0x08];
+const PERIOD: Glyph = [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c];
+const BLANK: Glyph = [0; GLYPH_HEIGHT as usize];
+
+/// Text in white on a translucent box, as rgba rows from the top with one
+/// pixel per font pixel. Front-ends scale it up by a whole number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Letters are drawn in upper case, characters without a glyph are left
+/// blank.
+pub fn rasterize(text: &str) -> TextImage {
+    let glyphs: Vec<Glyph> = text.chars().map(glyph).collect();
+
+    let text_width = (glyphs.len() as u32 * (GLYPH_WIDTH + 1)).saturating_sub(1);
+
+    let width = text_width + PADDING * 2;
+    let height = GLYPH_HEIGHT + PADDING * 2;
+
+    let mut pixels = BOX_COLOR.repeat((width * height) as usize);
+
+    for (i, glyph) in glyphs.iter().enumerate() {
+        let left = PADDING + i as u32 * (GLYPH_WIDTH + 1);
+
+        for (row, bits) in glyph.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (0x10 >> column) == 0 {
+                    continue;
+                }
+
+                let index = ((PADDING + row as u32) * width + left + column) as usize * 4;
+
+                pixels[index..index + 4].copy_from_slice(&TEXT_COLOR);
+            }
+        }
+    }
+
+    TextImage {
+        width,
+        height,
+        pixels,
+    }
+}
+
+fn glyph(c: char) -> Glyph {
+    match c.to_ascii_lowercase() {
+        c @ 'a'..='z' => LETTERS[c as usize - 'a' as usize],
+        c @ '0'..='9' => DIGITS[c as usize - '0' as usize],
+        ',' => COMMA,
+        '.' => PERIOD,
+        _ => BLANK,
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{gl_call, utils::*};
+
+use tetris_3d::render::Light;
+
+/// Sets the light uniforms of `shader` to `light`, see `res/fragment.glsl`.
+/// The shader has to be bound.
+pub fn apply(light: &Light, shader: &Shader) {
+    let direction = light.direction;
+    let color = light.color;
+
+    gl_call!(gl::Uniform3f(
+        shader.get_uniform("u_light_dir"),
+        direction.x,
+        direction.y,
+        direction.z
+    ));
+    gl_call!(gl::Uniform3f(
+        shader.get_uniform("u_light_color"),
+        color.x,
+        color.y,
+        color.z
+    ));
+
+    gl_call!(gl::Uniform1f(
+        shader.get_uniform("u_ambient"),
+        light.ambient
+    ));
+    gl_call!(gl::Uniform1f(
+        shader.get_uniform("u_specular"),
+        light.specular
+    ));
+    gl_call!(gl::Uniform1f(
+        shader.get_uniform("u_shininess"),
+        light.shininess
+    ));
+}
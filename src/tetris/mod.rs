@@ -1,32 +1,68 @@
 use super::{utils::*, *};
 
-mod camera;
-use camera::Camera;
-
-mod game_logic;
-use game_logic::GameLogic;
-
-use std::f32::consts::PI;
-use std::mem::size_of;
+mod light;
+mod overlay;
+
+mod renderer;
+use renderer::GlRenderer;
+
+mod sector_batch;
+mod shadow_map;
+
+use tetris_3d::gamepad::BUTTON_COUNT;
+use tetris_3d::input::CHAR_KEYS;
+use tetris_3d::render::Renderer;
+use tetris_3d::render::{scene, Camera, CameraSettings, DisplaySettings, Frame, Light};
+use tetris_3d::Key as GameKey;
+use tetris_3d::{Action as GameAction, Controller, Handling, KeyBindings};
+use tetris_3d::{Clock, Command, GameEvent, GameLogic, Spin, SystemClock};
+use tetris_3d::{Gamepad, GamepadSettings, PadState};
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::mpsc::Receiver;
+use std::time::Duration;
 
-use glm::identity;
 use nalgebra_glm as glm;
 
 use glfw::{Action, Context, Key, WindowHint, WindowMode};
 use glfw::{CursorMode, WindowEvent};
+use glfw::{GamepadAxis, GamepadButton, JoystickId};
+
+/// Radians per second the camera turns with the right stick fully tilted.
+const STICK_TURN_SPEED: f32 = 2.0;
+
+/// The shipped display settings, the game never writes to it.
+const DISPLAY_FILE: &str = "./res/display.toml";
 
 pub struct Tetris {
+    /// The size of the frame buffer in pixels, on high dpi screens it is
+    /// bigger than the window size in screen coordinates.
     screen_width: u32,
     screen_height: u32,
 
-    sector_height: f32,
+    /// The shipped settings, only what differs from them is saved.
+    display_defaults: DisplaySettings,
+    display: DisplaySettings,
+    /// Position and size of the window before going fullscreen, to go back.
+    windowed: ((i32, i32), (i32, i32)),
 
     cursor_disabled: bool,
 
-    shader: Shader,
+    title: String,
+
+    bindings: KeyBindings,
+    /// Actions whose keys are down, the camera moves while they are held.
+    held_actions: HashSet<GameAction>,
+    controller: Controller,
+    gamepad_settings: GamepadSettings,
+    /// The gamepads connected at the last poll.
+    gamepads: HashMap<JoystickId, Gamepad>,
+
+    renderer: GlRenderer,
     camera: Camera,
     game: GameLogic,
+    clock: SystemClock,
 
     events: Receiver<(f64, glfw::WindowEvent)>,
     window: glfw::Window,
@@ -35,12 +71,43 @@ pub struct Tetris {
 
 impl Tetris {
     pub fn new(screen_width: u32, screen_height: u32, title: &str) -> Self {
+        Self::create(
+            screen_width,
+            screen_height,
+            title,
+            GameLogic::new(15, 20),
+            false,
+        )
+    }
+
+    /// A hidden window that renders `game` into an offscreen frame buffer, to
+    /// take screenshots with `capture_frame`. It still needs a display with
+    /// OpenGL 3.3, `tetris_3d::render::capture` draws on the cpu instead.
+    pub fn new_hidden(screen_width: u32, screen_height: u32, game: GameLogic) -> Self {
+        Self::create(screen_width, screen_height, "3d-tetris", game, true)
+    }
+
+    fn create(
+        screen_width: u32,
+        screen_height: u32,
+        title: &str,
+        game: GameLogic,
+        hidden: bool,
+    ) -> Self {
         let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).expect("cannot init glfw");
 
         glfw.window_hint(WindowHint::ContextVersionMajor(3));
         glfw.window_hint(WindowHint::ContextVersionMinor(3));
         glfw.window_hint(WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
 
+        if hidden {
+            glfw.window_hint(WindowHint::Visible(false));
+        } else {
+            // the window grows with the content scale of the monitor, where
+            // the platform measures windows in pixels
+            glfw.window_hint(WindowHint::ScaleToMonitor(true));
+        }
+
         let (mut window, events) = glfw
             .create_window(screen_width, screen_height, title, WindowMode::Windowed)
             .expect("cannot create window");
@@ -48,11 +115,26 @@ impl Tetris {
         window.make_current();
         window.set_key_polling(true);
         window.set_cursor_pos_polling(true);
+        window.set_scroll_polling(true);
         window.set_framebuffer_size_polling(true);
-        window.set_cursor_mode(CursorMode::Disabled);
+
+        if !hidden {
+            window.set_cursor_mode(CursorMode::Disabled);
+        }
 
         let _gl = gl::load_with(|s| glfw.get_proc_address_raw(s));
 
+        // the frame buffer of a scaled window doesn't have the requested size
+        let (screen_width, screen_height) = if hidden {
+            (screen_width, screen_height)
+        } else {
+            let (width, height) = window.get_framebuffer_size();
+
+            gl_call!(gl::Viewport(0, 0, width, height));
+
+            (width as u32, height as u32)
+        };
+
         glfw.set_swap_interval(glfw::SwapInterval::Sync(1)); // open vsync
 
         gl_call!(gl::Enable(gl::BLEND));
@@ -62,112 +144,256 @@ impl Tetris {
 
         gl_call!(gl::Enable(gl::DEPTH_TEST));
 
-        Tetris {
+        let offscreen = if hidden {
+            Some(FrameBuffer::new_offscreen(screen_width, screen_height))
+        } else {
+            None
+        };
+
+        let windowed = (window.get_pos(), window.get_size());
+
+        // captures don't depend on the settings of whoever takes them
+        let (display_defaults, display) = if hidden {
+            (DisplaySettings::default(), DisplaySettings::default())
+        } else {
+            Self::load_display()
+        };
+
+        let mut tetris = Tetris {
             screen_width,
             screen_height,
 
-            sector_height: 1.0,
-            cursor_disabled: true,
+            display_defaults,
+            display,
+            windowed,
+
+            cursor_disabled: !hidden,
+
+            title: title.to_string(),
+
+            bindings: KeyBindings::load("./res/bindings.toml").unwrap_or_else(|error| {
+                println!("[WARNING]: {}, using the default key bindings", error);
+                KeyBindings::default()
+            }),
+            held_actions: HashSet::new(),
+            controller: Controller::new(Handling::load("./res/handling.toml").unwrap_or_else(
+                |error| {
+                    println!("[WARNING]: {}, using the default handling", error);
+                    Handling::default()
+                },
+            )),
+            gamepad_settings: GamepadSettings::load("./res/gamepad.toml").unwrap_or_else(|error| {
+                println!("[WARNING]: {}, using the default gamepad settings", error);
+                GamepadSettings::default()
+            }),
+            gamepads: HashMap::new(),
 
-            camera: Camera::new(0.05, 0.005),
+            camera: Self::start_camera(&game),
 
-            shader: Shader::new("./res/vertex.glsl", "./res/fragment.glsl"),
+            renderer: GlRenderer::new(&game, Light::default(), offscreen),
 
-            game: GameLogic::new(15, 20),
+            game,
+            clock: SystemClock::new(),
 
             glfw,
             window,
             events,
+        };
+
+        if tetris.display.fullscreen && !hidden {
+            tetris.apply_display();
         }
+
+        tetris
     }
 
     pub fn run(&mut self) {
-        let (vertices, indices) = self.create_shape(2.7, 3.0);
+        self.clock = SystemClock::new();
 
-        let vao = VertexArrayObject::new();
+        while !self.window.should_close() {
+            self.glfw.poll_events();
 
-        let vbo = BufferObject::new(gl::ARRAY_BUFFER, &vertices, gl::STATIC_DRAW);
+            let dt = self.clock.tick();
 
-        let ebo = BufferObject::new(gl::ELEMENT_ARRAY_BUFFER, &indices, gl::STATIC_DRAW);
+            self.handle_events();
+            self.handle_gamepads(dt);
 
-        vao.bind();
-        vbo.bind();
-        ebo.bind();
+            self.controller.update(dt, &mut self.game);
+            self.game.update(dt);
 
-        BufferObject::create_vertex(0, 3, gl::FLOAT, gl::FALSE, 3 * size_of::<GLfloat>(), 0);
+            self.camera.update(
+                &self.held_actions,
+                self.game.current_piece().pos.x as f32,
+                dt,
+            );
 
-        self.shader.bind();
+            self.handle_game_events();
 
-        let u_final_mat = self.shader.get_uniform("u_final_mat");
+            let frame = self.frame(self.glfw.get_time() as f32);
 
-        let u_color = self.shader.get_uniform("u_color");
+            self.renderer.draw(&self.game, &frame);
 
-        let aspect_ratio = (self.screen_width as f32) / (self.screen_height as f32);
+            self.window.swap_buffers();
+        }
+    }
 
-        let projection = glm::perspective(PI * 0.25, aspect_ratio, 0.1, 100.0);
+    /// Renders the current state of the game with the preview ring at `time`
+    /// seconds and saves it as a png. Only hidden windows can capture.
+    pub fn capture_frame(&mut self, time: f32, file_path: &str) -> image::ImageResult<()> {
+        let frame = self.frame(time);
 
-        let sector_angle = 2.0 * PI / (self.game.grid_width as f32);
+        self.renderer.draw(&self.game, &frame);
 
-        while !self.window.should_close() {
-            self.glfw.poll_events();
+        let pixels = self
+            .renderer
+            .read_pixels(frame.size)
+            .expect("only hidden windows can capture frames");
 
-            self.handle_events();
+        // gl starts from the bottom row
+        let image = image::RgbaImage::from_raw(frame.size.0, frame.size.1, pixels)
+            .expect("pixel buffer has the size of the frame");
 
-            self.camera.handle_key_events(&self.window);
+        image::imageops::flip_vertical(&image).save(file_path)
+    }
 
-            self.game.update();
+    fn frame(&self, time: f32) -> Frame {
+        Frame {
+            fov: self.display.fov_radians(),
+            ..self
+                .camera
+                .frame(&self.game, (self.screen_width, self.screen_height), time)
+        }
+    }
 
-            gl_call!(gl::ClearColor(
-                135.0 / 255.0,
-                206.0 / 255.0,
-                235.0 / 255.0,
-                1.0
-            ));
-            gl_call!(gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT));
+    /// The shipped display settings, and the user's own over them.
+    fn load_display() -> (DisplaySettings, DisplaySettings) {
+        let defaults = DisplaySettings::load(DISPLAY_FILE).unwrap_or_else(|error| {
+            println!("[WARNING]: {}, using the default display settings", error);
+            DisplaySettings::default()
+        });
+
+        let display = match Self::user_display_file() {
+            Some(file_path) => defaults.load_over(&file_path).unwrap_or_else(|error| {
+                println!(
+                    "[WARNING]: {} in {}, using the shipped display settings",
+                    error,
+                    file_path.display()
+                );
+                defaults
+            }),
+            None => defaults,
+        };
 
-            self.game.draw_grid_with(|x, y, color| {
-                if color.is_none() {
-                    return;
-                }
+        (defaults, display)
+    }
 
-                let color = color.unwrap();
+    /// Where the display settings changed while playing are kept, in the
+    /// config directory of the user.
+    fn user_display_file() -> Option<PathBuf> {
+        let env_dir = |name| {
+            std::env::var_os(name)
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+        };
 
-                let pos_y = (self.game.grid_height as f32 - y - 1.0) * self.sector_height;
+        let config_dir = env_dir("XDG_CONFIG_HOME")
+            .or_else(|| env_dir("APPDATA"))
+            .or_else(|| env_dir("HOME").map(|home| home.join(".config")))?;
 
-                let model = glm::scale(&identity::<f32, 4>(), &glm::vec3(0.2, 0.2, 0.2));
+        Some(config_dir.join("tetris-3d").join("display.toml"))
+    }
 
-                let model = glm::rotate(
-                    &model,
-                    x * sector_angle - PI * 0.5,
-                    &glm::vec3(0.0, 1.0, 0.0),
-                );
+    /// Switches between fullscreen and the window as it was before, and
+    /// keeps the choice for the next run.
+    fn toggle_fullscreen(&mut self) {
+        self.display.fullscreen = !self.display.fullscreen;
 
-                let model = glm::translate(&model, &glm::vec3(0.0, pos_y, 0.0));
+        self.apply_display();
 
-                let final_mat = projection * self.camera.view * model;
+        let saved = match Self::user_display_file() {
+            Some(file_path) => self.display.save_over(&self.display_defaults, file_path),
+            None => {
+                println!("[WARNING]: no config directory, fullscreen is not kept");
+                return;
+            }
+        };
 
-                gl_call!(gl::UniformMatrix4fv(
-                    u_final_mat,
-                    1,
-                    gl::FALSE,
-                    final_mat.as_ptr()
-                ));
+        if let Err(error) = saved {
+            println!("[WARNING]: {}, fullscreen is not kept", error);
+        }
+    }
 
-                gl_call!(gl::Uniform4f(u_color, color.x, color.y, color.z, color.w));
+    /// Moves the window to or from the primary monitor. The new frame buffer
+    /// size comes back as a `FramebufferSize` event.
+    fn apply_display(&mut self) {
+        let window = &mut self.window;
+
+        if !self.display.fullscreen {
+            let ((x, y), (width, height)) = self.windowed;
+
+            window.set_decorated(true);
+            window.set_monitor(
+                WindowMode::Windowed,
+                x,
+                y,
+                width as u32,
+                height as u32,
+                None,
+            );
+
+            return;
+        }
 
-                gl_call!(gl::DrawElements(
-                    gl::TRIANGLES,
-                    36,
-                    gl::UNSIGNED_INT,
-                    std::ptr::null()
-                ));
-            });
+        // fullscreen is only entered from the window
+        self.windowed = (window.get_pos(), window.get_size());
 
-            self.window.swap_buffers();
-        }
+        let borderless = self.display.borderless;
+
+        self.glfw.with_primary_monitor(|_, monitor| {
+            let (monitor, mode) = match monitor.and_then(|m| Some((m, m.get_video_mode()?))) {
+                Some(found) => found,
+                None => {
+                    println!("[WARNING]: no monitor to go fullscreen on");
+                    return;
+                }
+            };
+
+            if borderless {
+                let (x, y) = monitor.get_pos();
+
+                window.set_decorated(false);
+                window.set_monitor(WindowMode::Windowed, x, y, mode.width, mode.height, None);
+            } else {
+                window.set_monitor(
+                    WindowMode::FullScreen(monitor),
+                    0,
+                    0,
+                    mode.width,
+                    mode.height,
+                    Some(mode.refresh_rate),
+                );
+            }
+        });
+    }
+
+    /// The orbit camera in front of the current piece of `game`.
+    fn start_camera(game: &GameLogic) -> Camera {
+        let settings = CameraSettings::load("./res/camera.toml").unwrap_or_else(|error| {
+            println!("[WARNING]: {}, using the default camera settings", error);
+            CameraSettings::default()
+        });
+
+        Camera::new(
+            settings,
+            scene::sector_angle(game),
+            game.current_piece().pos.x as f32,
+        )
     }
 
     fn handle_events(&mut self) {
+        // the events are borrowed until the end of the loop
+        let mut toggle_fullscreen = false;
+
         for (_, event) in glfw::flush_messages(&self.events) {
             match event {
                 WindowEvent::FramebufferSize(width, height) => {
@@ -177,20 +403,24 @@ impl Tetris {
                     self.screen_height = height as u32;
                 }
                 WindowEvent::Key(key, _, action, _) => {
-                    if key == Key::Down {
-                        match action {
-                            Action::Repeat => {}
-                            _ => self.game.toggle_piece_drop(action == Action::Press),
-                        }
-                    }
+                    let key = match Self::game_key(key) {
+                        Some(key) => key,
+                        None => continue,
+                    };
+
+                    let bound = match self.bindings.action_for(key) {
+                        Some(bound) => bound,
+                        None => continue,
+                    };
 
                     if action == Action::Release {
-                        break;
+                        self.held_actions.remove(&bound);
+                    } else {
+                        self.held_actions.insert(bound);
                     }
 
-                    match key {
-                        Key::Escape => self.window.set_should_close(true),
-                        Key::F1 => {
+                    match (bound, action) {
+                        (GameAction::ToggleCursor, Action::Press) => {
                             self.window.set_cursor_mode(
                                 if self.window.get_cursor_mode() == CursorMode::Disabled {
                                     CursorMode::Normal
@@ -200,52 +430,176 @@ impl Tetris {
                             );
 
                             self.cursor_disabled = !self.cursor_disabled;
-                            self.camera.handle_mouse = false;
+                            self.camera.forget_mouse();
                         }
-                        Key::Left => self.game.move_piece(-1),
-                        Key::Right => self.game.move_piece(1),
-                        Key::Space => self.game.hard_drop_piece(),
-                        Key::Z if action == Action::Press => self.game.rotate_piece(),
-                        _ => {}
+                        (GameAction::ToggleCamera, Action::Press) => self.camera.toggle_mode(),
+                        (GameAction::NextCamera, Action::Press) => self.camera.next_preset(),
+                        (GameAction::ResetCamera, Action::Press) => self.camera.reset(),
+                        (GameAction::ToggleFullscreen, Action::Press) => toggle_fullscreen = true,
+                        (_, Action::Press) => {
+                            if self.controller.press(bound, &mut self.game) == Some(Command::Quit) {
+                                self.window.set_should_close(true);
+                            }
+                        }
+                        // the controller repeats held moves on its own
+                        (_, Action::Repeat) => {}
+                        (_, Action::Release) => self.controller.release(bound, &mut self.game),
                     }
                 }
+                WindowEvent::Scroll(_, y) => self.camera.scroll(y as f32),
                 WindowEvent::CursorPos(x, y) if self.cursor_disabled => {
                     self.camera.look_at(glm::vec2(x as f32, y as f32))
                 }
                 _ => {}
             }
         }
+
+        if toggle_fullscreen {
+            self.toggle_fullscreen();
+        }
     }
 
-    fn create_shape(&self, inner_radius: f32, outer_radius: f32) -> (Vec<glm::Vec3>, &[GLuint]) {
-        const INDICES: [GLuint; 36] = [
-            0, 1, 2, 4, 5, 6, 1, 2, 3, 5, 6, 7, 0, 4, 2, 1, 3, 7, 4, 2, 6, 1, 7, 5, 0, 1, 4, 2, 3,
-            6, 1, 4, 5, 3, 6, 7,
-        ];
+    /// The key in the bindings shared with the other front-ends.
+    fn game_key(key: Key) -> Option<GameKey> {
+        let key = match key {
+            Key::Left => GameKey::Left,
+            Key::Right => GameKey::Right,
+            Key::Up => GameKey::Up,
+            Key::Down => GameKey::Down,
+            Key::Space => GameKey::Space,
+            Key::Enter => GameKey::Enter,
+            Key::Escape => GameKey::Escape,
+            Key::LeftShift => GameKey::LeftShift,
+            Key::F1 => GameKey::F1,
+            Key::F11 => GameKey::F11,
+            // the printable keys have the ascii codes of a us keyboard, the
+            // letters in upper case
+            _ => {
+                let code = key as i32;
+
+                if !(0..128).contains(&code) {
+                    return None;
+                }
+
+                let c = (code as u8 as char).to_ascii_lowercase();
+
+                if !CHAR_KEYS.contains(c) {
+                    return None;
+                }
 
-        let sector_angle = 2.0 * PI / (self.game.grid_width as f32);
+                GameKey::Char(c)
+            }
+        };
 
-        let angle_vec = glm::vec3(sector_angle.cos(), 0.0, sector_angle.sin());
+        Some(key)
+    }
 
-        let mut vertices = vec![
-            glm::vec3(outer_radius, 0.0, 0.0),
-            angle_vec * outer_radius,
-            glm::vec3(inner_radius, 0.0, 0.0),
-            angle_vec * inner_radius,
-        ];
+    /// Polls every joystick with a gamepad mapping, so gamepads can be
+    /// plugged in and out while playing.
+    fn handle_gamepads(&mut self, dt: Duration) {
+        for id in (0..16).filter_map(JoystickId::from_i32) {
+            let joystick = self.glfw.get_joystick(id);
 
-        let size = vertices.len();
+            let state = match joystick.get_gamepad_state() {
+                Some(state) => Self::pad_state(&state),
+                None => {
+                    if let Some(mut gamepad) = self.gamepads.remove(&id) {
+                        println!("[INFO]: gamepad {:?} disconnected", id);
 
-        vertices.reserve(size * 2);
+                        for action in gamepad.disconnect() {
+                            self.controller.release(action, &mut self.game);
+                        }
+                    }
 
-        for i in 0..size {
-            let mut vec = vertices[i];
+                    continue;
+                }
+            };
 
-            vec.y = self.sector_height;
+            let settings = &self.gamepad_settings;
 
-            vertices.push(vec);
+            let gamepad = self.gamepads.entry(id).or_insert_with(|| {
+                let name = joystick.get_gamepad_name().unwrap_or_default();
+
+                println!("[INFO]: gamepad {:?} connected: {}", id, name);
+
+                Gamepad::new(settings.deadzone_for(&name))
+            });
+
+            for (action, pressed) in gamepad.poll(&state) {
+                if !pressed {
+                    self.controller.release(action, &mut self.game);
+                } else if self.controller.press(action, &mut self.game) == Some(Command::Quit) {
+                    self.window.set_should_close(true);
+                }
+            }
+
+            let (x, y) = gamepad.right_stick();
+
+            if (x, y) != (0.0, 0.0) {
+                self.camera
+                    .turn(glm::vec2(x, -y) * STICK_TURN_SPEED * dt.as_secs_f32());
+            }
+        }
+    }
+
+    /// The state in the button order and stick axes shared with the game core.
+    fn pad_state(state: &glfw::GamepadState) -> PadState {
+        let mut buttons = [false; BUTTON_COUNT];
+
+        for (i, pressed) in buttons.iter_mut().enumerate() {
+            let button = GamepadButton::from_i32(i as i32).expect("glfw has every button");
+
+            *pressed = state.get_button_state(button) == Action::Press;
+        }
+
+        PadState {
+            buttons,
+            left_stick: (
+                state.get_axis(GamepadAxis::AxisLeftX),
+                state.get_axis(GamepadAxis::AxisLeftY),
+            ),
+            right_stick: (
+                state.get_axis(GamepadAxis::AxisRightX),
+                state.get_axis(GamepadAxis::AxisRightY),
+            ),
+        }
+    }
+
+    fn handle_game_events(&mut self) {
+        // locks change the score, spawns cover restarts
+        let score_changed = self.game.drain_events().any(|event| {
+            matches!(
+                event,
+                GameEvent::PieceSpawned { .. } | GameEvent::Locked { .. }
+            )
+        });
+
+        if score_changed {
+            self.update_title();
         }
+    }
+
+    /// Shows the score and the last special clear in the window title.
+    fn update_title(&mut self) {
+        let stats = self.game.stats();
+
+        let last_lock = self.game.last_lock();
+
+        let spin = match last_lock.spin {
+            Spin::None => "",
+            Spin::Mini => " (mini t-spin)",
+            Spin::Full => " (t-spin)",
+        };
+
+        let perfect_clear = if last_lock.perfect_clear {
+            " (perfect clear)"
+        } else {
+            ""
+        };
 
-        (vertices, &INDICES)
+        self.window.set_title(&format!(
+            "{} - score: {} level: {} lines: {}{}{}",
+            self.title, stats.score, stats.level, stats.lines, spin, perfect_clear
+        ));
     }
 }
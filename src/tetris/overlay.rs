@@ -0,0 +1,105 @@
+use crate::{gl_call, utils::*};
+
+use tetris_3d::render::text;
+
+use std::mem::size_of;
+
+/// Texture unit of the text, the shadow map has the first one.
+const TEXTURE_INDEX: GLenum = 1;
+
+/// About how much of the frame height the text box takes.
+const TEXT_HEIGHT: f32 = 0.06;
+/// The most of the frame width it can take.
+const MAX_TEXT_WIDTH: f32 = 0.9;
+
+/// Draws a line of text in the middle of the frame over the scene, for the
+/// prompts of the states where the game waits for a key.
+pub struct Overlay {
+    shader: Shader,
+    vao: VertexArrayObject,
+    _quad: BufferObject,
+
+    u_rect: Uniform,
+
+    // the text only goes to the gpu when it changes
+    text: Option<(&'static str, Texture, (u32, u32))>,
+}
+
+impl Overlay {
+    pub fn new() -> Self {
+        let shader = Shader::new("./res/overlay_vertex.glsl", "./res/overlay_fragment.glsl");
+
+        shader.bind();
+
+        gl_call!(gl::Uniform1i(
+            shader.get_uniform("u_text"),
+            TEXTURE_INDEX as GLint
+        ));
+
+        let corners: [GLfloat; 8] = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+
+        let vao = VertexArrayObject::new();
+        let quad = BufferObject::new(gl::ARRAY_BUFFER, &corners, gl::STATIC_DRAW);
+
+        vao.bind();
+
+        quad.bind();
+        BufferObject::create_vertex(0, 2, gl::FLOAT, gl::FALSE, 2 * size_of::<GLfloat>(), 0);
+
+        vao.unbind();
+
+        Overlay {
+            u_rect: shader.get_uniform("u_rect"),
+
+            shader,
+            vao,
+            _quad: quad,
+
+            text: None,
+        }
+    }
+
+    /// Draws `text` over everything in a frame of `size` pixels.
+    pub fn draw(&mut self, text: &'static str, size: (u32, u32)) {
+        if self.text.as_ref().map(|(drawn, ..)| *drawn) != Some(text) {
+            let image = text::rasterize(text);
+            let texture =
+                Texture::from_rgba(image.width, image.height, &image.pixels, TEXTURE_INDEX);
+
+            self.text = Some((text, texture, (image.width, image.height)));
+        }
+
+        let (_, texture, (width, height)) = self.text.as_ref().unwrap();
+
+        let (frame_width, frame_height) = (size.0.max(1) as f32, size.1.max(1) as f32);
+
+        // whole font pixels keep the glyphs sharp
+        let scale = (frame_height * TEXT_HEIGHT / *height as f32)
+            .min(frame_width * MAX_TEXT_WIDTH / *width as f32)
+            .floor()
+            .max(1.0);
+
+        let rect_width = *width as f32 * scale / frame_width * 2.0;
+        let rect_height = *height as f32 * scale / frame_height * 2.0;
+
+        self.shader.bind();
+
+        gl_call!(gl::Uniform4f(
+            self.u_rect,
+            -rect_width / 2.0,
+            -rect_height / 2.0,
+            rect_width,
+            rect_height
+        ));
+
+        texture.bind();
+
+        gl_call!(gl::Disable(gl::DEPTH_TEST));
+
+        self.vao.bind();
+        gl_call!(gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4));
+        self.vao.unbind();
+
+        gl_call!(gl::Enable(gl::DEPTH_TEST));
+    }
+}
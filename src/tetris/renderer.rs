@@ -0,0 +1,225 @@
+use crate::{gl_call, utils::*};
+
+use super::light;
+use super::overlay::Overlay;
+use super::sector_batch::SectorBatch;
+use super::shadow_map::ShadowMap;
+
+use tetris_3d::render::{scene, Frame, Light, Mesh, Renderer};
+use tetris_3d::{GameLogic, GameState};
+
+use nalgebra_glm as glm;
+
+const SHADOW_MAP_SIZE: u32 = 2048;
+
+/// Draws a `GameLogic` with OpenGL, into the window or an offscreen frame
+/// buffer. It needs a current context for its whole lifetime.
+pub struct GlRenderer {
+    shader: Shader,
+    shadow_map: ShadowMap,
+
+    // the light looks at the middle of the cylinder from above, far enough
+    // to see the whole floor
+    light_center: glm::Vec3,
+    light_radius: f32,
+
+    // the settled cells only change with the grid, everything else moves
+    // every frame
+    grid_batch: SectorBatch,
+    piece_batch: SectorBatch,
+    floor_batch: SectorBatch,
+
+    grid_key: Option<(u64, GameState)>,
+
+    // frames go here instead of the window when set
+    target: Option<FrameBuffer>,
+
+    // the prompt of the states where the game waits
+    overlay: Overlay,
+
+    u_view_projection: Uniform,
+    u_column_offset: Uniform,
+    u_view_pos: Uniform,
+
+    // sector and floor meshes the batches draw from
+    _meshes: [BufferObject; 4],
+}
+
+impl GlRenderer {
+    pub fn new(game: &GameLogic, light: Light, target: Option<FrameBuffer>) -> Self {
+        let sector_angle = scene::sector_angle(game);
+
+        let sector = Mesh::sector(
+            sector_angle,
+            scene::SECTOR_HEIGHT,
+            scene::INNER_RADIUS,
+            scene::OUTER_RADIUS,
+        );
+
+        let vbo = BufferObject::new(gl::ARRAY_BUFFER, &sector.vertices, gl::STATIC_DRAW);
+
+        let ebo = BufferObject::new(gl::ELEMENT_ARRAY_BUFFER, &sector.indices, gl::STATIC_DRAW);
+
+        let grid_batch = SectorBatch::new(&vbo, &ebo, sector.indices.len());
+        let piece_batch = SectorBatch::new(&vbo, &ebo, sector.indices.len());
+
+        let floor = Mesh::floor(scene::FLOOR_SIZE);
+
+        let floor_vbo = BufferObject::new(gl::ARRAY_BUFFER, &floor.vertices, gl::STATIC_DRAW);
+
+        let floor_ebo =
+            BufferObject::new(gl::ELEMENT_ARRAY_BUFFER, &floor.indices, gl::STATIC_DRAW);
+
+        let floor_batch = SectorBatch::new(&floor_vbo, &floor_ebo, floor.indices.len());
+
+        let shader = Shader::new("./res/vertex.glsl", "./res/fragment.glsl");
+
+        shader.bind();
+
+        gl_call!(gl::Uniform1f(
+            shader.get_uniform("u_sector_angle"),
+            sector_angle
+        ));
+
+        gl_call!(gl::Uniform1i(shader.get_uniform("u_shadow_map"), 0));
+
+        let shadow_map = ShadowMap::new(SHADOW_MAP_SIZE, 0);
+
+        shadow_map.set_sector_angle(sector_angle);
+
+        let (light_center, light_radius) = scene::bounds(game);
+
+        let mut renderer = GlRenderer {
+            u_view_projection: shader.get_uniform("u_view_projection"),
+            u_column_offset: shader.get_uniform("u_column_offset"),
+            u_view_pos: shader.get_uniform("u_view_pos"),
+
+            shader,
+            shadow_map,
+
+            light_center,
+            light_radius,
+
+            grid_batch,
+            piece_batch,
+            floor_batch,
+
+            grid_key: None,
+
+            target,
+
+            overlay: Overlay::new(),
+
+            _meshes: [vbo, ebo, floor_vbo, floor_ebo],
+        };
+
+        renderer.set_light(light);
+
+        renderer
+    }
+
+    /// Reads back the last frame drawn into the target as rgba rows,
+    /// starting from the bottom row.
+    pub fn read_pixels(&self, size: (u32, u32)) -> Option<Vec<u8>> {
+        self.target
+            .as_ref()
+            .map(|target| target.read_pixels(size.0, size.1))
+    }
+}
+
+impl Renderer for GlRenderer {
+    /// Replaces the light of the scene, the shadows follow it.
+    fn set_light(&mut self, light: Light) {
+        let light_space = ShadowMap::light_space(&light, self.light_center, self.light_radius);
+
+        self.shadow_map.set_light_space(&light_space);
+
+        self.shader.bind();
+
+        gl_call!(gl::UniformMatrix4fv(
+            self.shader.get_uniform("u_light_space"),
+            1,
+            gl::FALSE,
+            light_space.as_ptr()
+        ));
+
+        light::apply(&light, &self.shader);
+    }
+
+    fn draw(&mut self, game: &GameLogic, frame: &Frame) {
+        let state = game.state();
+
+        // the flash of a line clear changes the colors every frame
+        let key = (game.grid_revision(), state);
+
+        if self.grid_key != Some(key) || state == GameState::LineClearAnimating {
+            self.grid_batch.upload(&scene::grid_sectors(game));
+            self.floor_batch.upload(&[scene::floor_sector(game)]);
+
+            self.grid_key = Some(key);
+        }
+
+        self.piece_batch.upload(&scene::moving_sectors(game, frame));
+
+        let (grid_batch, piece_batch) = (&self.grid_batch, &self.piece_batch);
+
+        let draw_blocks = |u_column_offset: Uniform| {
+            gl_call!(gl::Uniform1f(u_column_offset, frame.column_offset));
+            grid_batch.draw();
+            piece_batch.draw();
+        };
+
+        // the floor only receives shadows
+        self.shadow_map.render(draw_blocks);
+
+        if let Some(target) = &self.target {
+            target.bind();
+        }
+
+        gl_call!(gl::Viewport(
+            0,
+            0,
+            frame.size.0 as GLsizei,
+            frame.size.1 as GLsizei
+        ));
+
+        let sky = scene::SKY_COLOR;
+
+        gl_call!(gl::ClearColor(sky[0], sky[1], sky[2], 1.0));
+        gl_call!(gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT));
+
+        self.shader.bind();
+
+        self.shadow_map.bind_texture();
+
+        let view_projection = frame.view_projection();
+
+        gl_call!(gl::UniformMatrix4fv(
+            self.u_view_projection,
+            1,
+            gl::FALSE,
+            view_projection.as_ptr()
+        ));
+
+        gl_call!(gl::Uniform3f(
+            self.u_view_pos,
+            frame.eye.x,
+            frame.eye.y,
+            frame.eye.z
+        ));
+
+        draw_blocks(self.u_column_offset);
+
+        // the floor doesn't turn with the cylinder
+        gl_call!(gl::Uniform1f(self.u_column_offset, 0.0));
+        self.floor_batch.draw();
+
+        if let Some(prompt) = scene::state_prompt(state) {
+            self.overlay.draw(prompt, frame.size);
+        }
+
+        if let Some(target) = &self.target {
+            target.unbind();
+        }
+    }
+}
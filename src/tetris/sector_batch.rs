@@ -0,0 +1,112 @@
+use crate::{gl_call, utils::*};
+
+use tetris_3d::render::Sector;
+
+use std::mem::size_of;
+
+/// Per instance attributes of a sector, laid out like `a_block` and `a_color`
+/// in `res/vertex.glsl`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SectorInstance {
+    // column, height in sector heights and scale
+    block: [f32; 3],
+    color: [f32; 4],
+}
+
+/// A list of sectors drawn with a single instanced draw call. The sectors only
+/// reach the gpu on `upload`, so a batch that doesn't change can be drawn
+/// every frame for free.
+pub struct SectorBatch {
+    vao: VertexArrayObject,
+    instance_buffer: BufferObject,
+
+    count: usize,
+
+    index_count: usize,
+}
+
+impl SectorBatch {
+    /// `vbo` and `ebo` hold the mesh with `index_count` indices, they can be
+    /// shared by batches.
+    pub fn new(vbo: &BufferObject, ebo: &BufferObject, index_count: usize) -> Self {
+        let vao = VertexArrayObject::new();
+
+        let instance_buffer =
+            BufferObject::new::<SectorInstance>(gl::ARRAY_BUFFER, &[], gl::DYNAMIC_DRAW);
+
+        vao.bind();
+
+        // position and normal
+        let vertex_stride = 6 * size_of::<GLfloat>();
+
+        vbo.bind();
+        BufferObject::create_vertex(0, 3, gl::FLOAT, gl::FALSE, vertex_stride, 0);
+        BufferObject::create_vertex(
+            3,
+            3,
+            gl::FLOAT,
+            gl::FALSE,
+            vertex_stride,
+            3 * size_of::<GLfloat>(),
+        );
+
+        let stride = size_of::<SectorInstance>();
+
+        instance_buffer.bind();
+        BufferObject::create_vertex(1, 3, gl::FLOAT, gl::FALSE, stride, 0);
+        BufferObject::create_vertex(2, 4, gl::FLOAT, gl::FALSE, stride, 3 * size_of::<GLfloat>());
+        BufferObject::set_divisor(1, 1);
+        BufferObject::set_divisor(2, 1);
+
+        ebo.bind();
+
+        vao.unbind();
+
+        SectorBatch {
+            vao,
+            instance_buffer,
+
+            count: 0,
+
+            index_count,
+        }
+    }
+
+    /// Replaces the drawn sectors.
+    pub fn upload(&mut self, sectors: &[Sector]) {
+        let instances: Vec<SectorInstance> = sectors
+            .iter()
+            .map(|sector| SectorInstance {
+                block: [sector.column, sector.height, sector.scale],
+                color: [
+                    sector.color.x,
+                    sector.color.y,
+                    sector.color.z,
+                    sector.color.w,
+                ],
+            })
+            .collect();
+
+        self.instance_buffer.set_data(&instances);
+        self.count = instances.len();
+    }
+
+    pub fn draw(&self) {
+        if self.count == 0 {
+            return;
+        }
+
+        self.vao.bind();
+
+        gl_call!(gl::DrawElementsInstanced(
+            gl::TRIANGLES,
+            self.index_count as GLsizei,
+            gl::UNSIGNED_INT,
+            std::ptr::null(),
+            self.count as GLsizei
+        ));
+
+        self.vao.unbind();
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{gl_call, utils::*};
+
+use tetris_3d::render::Light;
+
+use nalgebra_glm as glm;
+
+/// Depth of the scene as seen from the light, `fragment.glsl` compares against
+/// it to find the fragments in shadow.
+pub struct ShadowMap {
+    size: u32,
+
+    shader: Shader,
+    depth: Texture,
+    frame_buffer: FrameBuffer,
+
+    u_column_offset: Uniform,
+}
+
+impl ShadowMap {
+    /// `index` is the texture unit the depth is read from.
+    pub fn new(size: u32, index: GLenum) -> Self {
+        let shader = Shader::new("./res/shadow_vertex.glsl", "./res/shadow_fragment.glsl");
+
+        let depth = Texture::new_depth(size, size, index);
+
+        let frame_buffer = FrameBuffer::new_depth(&depth);
+
+        let u_column_offset = shader.get_uniform("u_column_offset");
+
+        ShadowMap {
+            size,
+
+            shader,
+            depth,
+            frame_buffer,
+
+            u_column_offset,
+        }
+    }
+
+    /// An orthographic projection from `light` that covers the sphere at
+    /// `center`, the light sits outside of it looking in.
+    pub fn light_space(light: &Light, center: glm::Vec3, radius: f32) -> glm::Mat4 {
+        let direction = glm::normalize(&light.direction);
+
+        // a straight down light can't use y as up
+        let up = if direction.y.abs() > 0.99 {
+            glm::vec3(0.0, 0.0, -1.0)
+        } else {
+            glm::vec3(0.0, 1.0, 0.0)
+        };
+
+        let eye = center - direction * radius * 2.0;
+
+        let projection = glm::ortho(-radius, radius, -radius, radius, radius, radius * 3.0);
+
+        projection * glm::look_at(&eye, &center, &up)
+    }
+
+    pub fn set_light_space(&self, light_space: &glm::Mat4) {
+        self.shader.bind();
+
+        gl_call!(gl::UniformMatrix4fv(
+            self.shader.get_uniform("u_light_space"),
+            1,
+            gl::FALSE,
+            light_space.as_ptr()
+        ));
+    }
+
+    pub fn set_sector_angle(&self, sector_angle: f32) {
+        self.shader.bind();
+
+        gl_call!(gl::Uniform1f(
+            self.shader.get_uniform("u_sector_angle"),
+            sector_angle
+        ));
+    }
+
+    /// Renders the depth of the scene, `draw` gets the column offset uniform
+    /// of the shadow shader. The viewport has to be restored afterwards.
+    pub fn render(&self, draw: impl Fn(Uniform)) {
+        self.frame_buffer.bind();
+
+        gl_call!(gl::Viewport(
+            0,
+            0,
+            self.size as GLsizei,
+            self.size as GLsizei
+        ));
+        gl_call!(gl::Clear(gl::DEPTH_BUFFER_BIT));
+
+        self.shader.bind();
+
+        draw(self.u_column_offset);
+
+        self.frame_buffer.unbind();
+    }
+
+    pub fn bind_texture(&self) {
+        self.depth.bind();
+    }
+}
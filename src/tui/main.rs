@@ -0,0 +1,179 @@
+mod scene_view;
+mod view;
+
+use scene_view::SceneView;
+
+use tetris_3d::{
+    Action, Clock, Command, Controller, GameLogic, Handling, Key, KeyBindings, SystemClock,
+};
+
+use crossterm::event::{
+    self, Event, KeyCode, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+    PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use crossterm::{cursor, execute, terminal};
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+const FRAME_TIME: Duration = Duration::from_millis(16);
+/// How long soft dropping goes on after the last soft drop key, for terminals
+/// that don't report key releases.
+const SOFT_DROP_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// tetris-3d-tui [--software], the board is drawn flat unless --software
+/// asks for the 3d scene, rendered on the cpu.
+fn main() -> io::Result<()> {
+    let software = std::env::args().skip(1).any(|arg| arg == "--software");
+
+    let bindings = KeyBindings::load("./res/bindings.toml").unwrap_or_else(|error| {
+        println!("[WARNING]: {}, using the default key bindings", error);
+        KeyBindings::default()
+    });
+
+    let mut stdout = io::stdout();
+
+    terminal::enable_raw_mode()?;
+
+    let key_releases = terminal::supports_keyboard_enhancement().unwrap_or(false);
+
+    execute!(
+        stdout,
+        terminal::EnterAlternateScreen,
+        terminal::Clear(terminal::ClearType::All),
+        cursor::Hide
+    )?;
+
+    if key_releases {
+        execute!(
+            stdout,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+        )?;
+    }
+
+    let result = run(&mut stdout, &bindings, key_releases, software);
+
+    // the terminal has to be given back even after an error
+    if key_releases {
+        execute!(stdout, PopKeyboardEnhancementFlags)?;
+    }
+
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+fn run(
+    out: &mut impl Write,
+    bindings: &KeyBindings,
+    key_releases: bool,
+    software: bool,
+) -> io::Result<()> {
+    let mut game = GameLogic::new(15, 20);
+    let mut scene = software.then(|| SceneView::new(&game));
+    let mut clock = SystemClock::new();
+
+    let mut controller = Controller::new(Handling::load("./res/handling.toml").unwrap_or_else(
+        |error| {
+            println!("[WARNING]: {}, using the default handling", error);
+            Handling::default()
+        },
+    ));
+
+    // last time the soft drop key came in while soft dropping
+    let mut soft_drop_since: Option<Instant> = None;
+
+    loop {
+        if event::poll(FRAME_TIME)? {
+            while event::poll(Duration::ZERO)? {
+                let key_event = match event::read()? {
+                    Event::Key(key_event) => key_event,
+                    Event::Resize(..) => {
+                        execute!(out, terminal::Clear(terminal::ClearType::All))?;
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                if key_event.modifiers.contains(KeyModifiers::CONTROL)
+                    && key_event.code == KeyCode::Char('c')
+                {
+                    return Ok(());
+                }
+
+                let action = match game_key(key_event.code).and_then(|key| bindings.action_for(key))
+                {
+                    Some(action) => action,
+                    None => continue,
+                };
+
+                match key_event.kind {
+                    KeyEventKind::Press => {
+                        if let Some(scene) = &mut scene {
+                            scene.press(action);
+                        }
+
+                        if controller.press(action, &mut game) == Some(Command::Quit) {
+                            return Ok(());
+                        }
+
+                        // without releases every press is a tap and the key
+                        // repeat of the terminal does the repeating
+                        if !key_releases {
+                            if action == Action::SoftDrop {
+                                soft_drop_since = Some(Instant::now());
+                            } else {
+                                controller.release(action, &mut game);
+                            }
+                        }
+                    }
+                    // the controller repeats held moves on its own
+                    KeyEventKind::Repeat => {}
+                    KeyEventKind::Release => controller.release(action, &mut game),
+                }
+            }
+        }
+
+        if soft_drop_since.is_some_and(|t| t.elapsed() > SOFT_DROP_TIMEOUT) {
+            controller.release(Action::SoftDrop, &mut game);
+            soft_drop_since = None;
+        }
+
+        let dt = clock.tick();
+
+        controller.update(dt, &mut game);
+        game.update(dt);
+
+        // nothing here reacts to them, they only need to be taken out
+        game.drain_events().for_each(drop);
+
+        match &mut scene {
+            Some(scene) => {
+                scene.update(&game, dt);
+                scene.draw(out, &game)?;
+            }
+            None => view::draw(out, &game)?,
+        }
+    }
+}
+
+/// The key in the bindings shared with the window front-end.
+fn game_key(code: KeyCode) -> Option<Key> {
+    let key = match code {
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Char(' ') => Key::Space,
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Esc => Key::Escape,
+        KeyCode::F(1) => Key::F1,
+        KeyCode::F(11) => Key::F11,
+        KeyCode::Char(c) => Key::Char(c),
+        _ => return None,
+    };
+
+    Some(key)
+}
@@ -0,0 +1,118 @@
+use super::view::{state_message, status_line};
+
+use tetris_3d::render::{scene, Camera, CameraSettings, Light, Renderer, SoftwareRenderer};
+use tetris_3d::{Action, GameLogic};
+
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{cursor::MoveTo, queue};
+
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Top half of a terminal cell, the bottom half is its background.
+const HALF_BLOCK: &str = "▀";
+
+/// The 3d scene of the window front-end drawn on the cpu, for terminals on
+/// machines without OpenGL 3.3. Every cell shows two pixels on top of each
+/// other, which makes them about square.
+pub struct SceneView {
+    camera: Camera,
+    renderer: SoftwareRenderer,
+    /// Seconds since the start, for the preview ring.
+    time: f32,
+}
+
+impl SceneView {
+    pub fn new(game: &GameLogic) -> Self {
+        let settings = CameraSettings::load("./res/camera.toml").unwrap_or_else(|error| {
+            println!("[WARNING]: {}, using the default camera settings", error);
+            CameraSettings::default()
+        });
+
+        SceneView {
+            camera: Camera::new(
+                settings,
+                scene::sector_angle(game),
+                game.current_piece().pos.x as f32,
+            ),
+            renderer: SoftwareRenderer::new(game, Light::default()),
+            time: 0.0,
+        }
+    }
+
+    /// The camera actions that work without key releases.
+    pub fn press(&mut self, action: Action) {
+        match action {
+            Action::NextCamera => self.camera.next_preset(),
+            Action::ResetCamera => self.camera.reset(),
+            _ => {}
+        }
+    }
+
+    pub fn update(&mut self, game: &GameLogic, dt: Duration) {
+        self.camera
+            .update(&HashSet::new(), game.current_piece().pos.x as f32, dt);
+
+        self.time += dt.as_secs_f32();
+    }
+
+    /// Fills the terminal below the status line with the scene.
+    pub fn draw(&mut self, out: &mut impl Write, game: &GameLogic) -> io::Result<()> {
+        let (columns, rows) = terminal::size()?;
+        let rows = rows.saturating_sub(1);
+
+        queue!(out, MoveTo(0, 0), Clear(ClearType::UntilNewLine))?;
+        queue!(
+            out,
+            SetForegroundColor(Color::White),
+            Print(status_line(game)),
+            Print("  "),
+            Print(state_message(game.state()))
+        )?;
+
+        let size = (u32::from(columns), u32::from(rows) * 2);
+
+        if size.0 == 0 || size.1 == 0 {
+            return out.flush();
+        }
+
+        let frame = self.camera.frame(game, size, self.time);
+
+        self.renderer.draw(game, &frame);
+
+        let pixels = self.renderer.pixels();
+        let color_at = |x: u32, y: u32| {
+            let i = ((y * size.0 + x) * 4) as usize;
+
+            Color::Rgb {
+                r: pixels[i],
+                g: pixels[i + 1],
+                b: pixels[i + 2],
+            }
+        };
+
+        for row in 0..u32::from(rows) {
+            queue!(out, MoveTo(0, 1 + row as u16))?;
+
+            // the colors only change where the picture does
+            let mut colors = None;
+
+            for x in 0..size.0 {
+                let cell = (color_at(x, row * 2), color_at(x, row * 2 + 1));
+
+                if colors != Some(cell) {
+                    queue!(out, SetForegroundColor(cell.0), SetBackgroundColor(cell.1))?;
+                    colors = Some(cell);
+                }
+
+                queue!(out, Print(HALF_BLOCK))?;
+            }
+        }
+
+        queue!(out, ResetColor)?;
+
+        out.flush()
+    }
+}
@@ -0,0 +1,261 @@
+use tetris_3d::game_logic::Vec2;
+use tetris_3d::render::scene::{state_prompt, state_tint};
+use tetris_3d::{DroppingPiece, GameLogic, GameState, Spin};
+
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::{cursor::MoveTo, queue};
+
+use nalgebra_glm as glm;
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+
+const BLOCK: &str = "██";
+const GHOST: &str = "░░";
+const EMPTY: &str = " ·";
+
+/// Screen columns between the left edge and the side panel.
+const PANEL_GAP: u16 = 4;
+
+/// What a terminal cell pair of the board shows.
+#[derive(Clone, Copy)]
+struct Cell {
+    text: &'static str,
+    color: Color,
+}
+
+/// Draws the cylinder unrolled, turned so the current piece stays in the
+/// middle like in the 3d view. The arrows on the sides mark that the board
+/// wraps around and the one on top where the first column is.
+pub fn draw(out: &mut impl Write, game: &GameLogic) -> io::Result<()> {
+    let width = game.grid_width;
+    let height = game.grid_height;
+
+    let first_column = game.current_piece().pos.x - width as i32 / 2;
+    let column_at = |i: usize| (first_column + i as i32).rem_euclid(width as i32) as usize;
+
+    let cells = board_cells(game);
+
+    queue!(out, MoveTo(0, 0), Clear(ClearType::UntilNewLine))?;
+    queue!(out, Print(status_line(game)))?;
+
+    let ruler: String = (0..width)
+        .map(|i| if column_at(i) == 0 { "▼ " } else { "  " })
+        .collect();
+
+    queue!(
+        out,
+        MoveTo(0, 1),
+        SetForegroundColor(Color::DarkGrey),
+        Print(format!("  {}  ", ruler))
+    )?;
+
+    for y in 0..height {
+        queue!(
+            out,
+            MoveTo(0, 2 + y as u16),
+            SetForegroundColor(Color::DarkGrey),
+            Print("◀│")
+        )?;
+
+        for i in 0..width {
+            let cell = cells[y * width + column_at(i)];
+
+            queue!(out, SetForegroundColor(cell.color), Print(cell.text))?;
+        }
+
+        queue!(out, SetForegroundColor(Color::DarkGrey), Print("│▶"))?;
+    }
+
+    queue!(
+        out,
+        MoveTo(0, 2 + height as u16),
+        Print(format!(" └{}┘ ", "──".repeat(width)))
+    )?;
+
+    let panel_x = (width * 2) as u16 + PANEL_GAP + 4;
+
+    draw_panel(out, game, panel_x)?;
+
+    queue!(out, ResetColor)?;
+
+    out.flush()
+}
+
+/// The grid with the current piece and its ghost on top, by grid index.
+fn board_cells(game: &GameLogic) -> Vec<Cell> {
+    let width = game.grid_width;
+    let state = game.state();
+
+    let empty = Cell {
+        text: EMPTY,
+        color: Color::DarkGrey,
+    };
+
+    let cells = RefCell::new(vec![empty; width * game.grid_height]);
+
+    game.draw_cells_with(|x, y, color| {
+        if let Some(color) = color {
+            cells.borrow_mut()[y * width + x] = Cell {
+                text: BLOCK,
+                color: to_color(state_tint(state, color)),
+            };
+        }
+    });
+
+    // the piece comes before its ghost, which only goes on empty cells
+    game.draw_piece_with(|x, y, color| {
+        let x = x.round() as i32;
+        let y = y.round() as i32;
+
+        if y < 0 || y >= game.grid_height as i32 {
+            return;
+        }
+
+        if let Some(color) = color {
+            let cell =
+                &mut cells.borrow_mut()[y as usize * width + x.rem_euclid(width as i32) as usize];
+
+            let text = if color.w < 1.0 { GHOST } else { BLOCK };
+
+            if text == BLOCK || cell.text == EMPTY {
+                *cell = Cell {
+                    text,
+                    color: to_color(state_tint(state, color)),
+                };
+            }
+        }
+    });
+
+    cells.into_inner()
+}
+
+/// Score, level and lines, with the spin of the last lock.
+pub fn status_line(game: &GameLogic) -> String {
+    let stats = game.stats();
+
+    let spin = match game.last_lock().spin {
+        Spin::None => "",
+        Spin::Mini => " (mini t-spin)",
+        Spin::Full => " (t-spin)",
+    };
+
+    format!(
+        " score: {}  level: {}  lines: {}{}",
+        stats.score, stats.level, stats.lines, spin
+    )
+}
+
+/// Held piece, the preview queue and what the keys do right now.
+fn draw_panel(out: &mut impl Write, game: &GameLogic, x: u16) -> io::Result<()> {
+    let mut y = 2;
+
+    queue!(
+        out,
+        MoveTo(x, y),
+        SetForegroundColor(Color::Grey),
+        Print("hold")
+    )?;
+
+    // the colors come from the game, a piece keeps its color when it comes
+    // into play
+    let held_color = RefCell::new(None);
+
+    game.draw_hold_with(|_, _, color| *held_color.borrow_mut() = color);
+
+    draw_mini_piece(out, game.held_piece(), held_color.into_inner(), x, y + 1)?;
+
+    y += 4;
+
+    queue!(
+        out,
+        MoveTo(x, y),
+        SetForegroundColor(Color::Grey),
+        Print("next")
+    )?;
+
+    let preview: Vec<usize> = game.preview().collect();
+    let colors = RefCell::new(vec![None; preview.len()]);
+
+    game.draw_preview_with(|i, _, color| colors.borrow_mut()[i] = color);
+
+    let colors = colors.into_inner();
+
+    for i in 0..game.preview_len() {
+        draw_mini_piece(
+            out,
+            preview.get(i).copied(),
+            colors.get(i).copied().flatten(),
+            x,
+            y + 1 + i as u16 * 3,
+        )?;
+    }
+
+    y += 1 + game.preview_len() as u16 * 3;
+
+    queue!(
+        out,
+        MoveTo(x, y),
+        Clear(ClearType::UntilNewLine),
+        SetForegroundColor(Color::White),
+        Print(state_message(game.state()))
+    )
+}
+
+/// What the keys do in states other than playing.
+pub fn state_message(state: GameState) -> &'static str {
+    state_prompt(state).unwrap_or("")
+}
+
+/// A piece in a 4x2 box, or an empty box.
+fn draw_mini_piece(
+    out: &mut impl Write,
+    piece_type: Option<usize>,
+    color: Option<glm::Vec4>,
+    x: u16,
+    y: u16,
+) -> io::Result<()> {
+    let mut rows = [[false; 4]; 2];
+
+    if let Some(piece_type) = piece_type {
+        let piece = DroppingPiece::new(Vec2::new(0, 0), piece_type);
+        let blocks: Vec<Vec2> = (0..4).map(|i| piece.get_pos_at(i)).collect();
+
+        let min_x = blocks.iter().map(|v| v.x).min().unwrap_or(0);
+        let min_y = blocks.iter().map(|v| v.y).min().unwrap_or(0);
+
+        for v in blocks {
+            rows[(v.y - min_y) as usize][(v.x - min_x) as usize] = true;
+        }
+    }
+
+    let color = color.map_or(Color::DarkGrey, to_color);
+
+    for (i, row) in rows.iter().enumerate() {
+        let line: String = row
+            .iter()
+            .map(|&filled| if filled { BLOCK } else { "  " })
+            .collect();
+
+        queue!(
+            out,
+            MoveTo(x, y + i as u16),
+            SetForegroundColor(color),
+            Print(line)
+        )?;
+    }
+
+    Ok(())
+}
+
+fn to_color(color: glm::Vec4) -> Color {
+    // the terminal can't blend, the alpha darkens instead
+    let channel = |c: f32| (c * color.w.max(0.5) * 255.0).round().clamp(0.0, 255.0) as u8;
+
+    Color::Rgb {
+        r: channel(color.x),
+        g: channel(color.y),
+        b: channel(color.z),
+    }
+}
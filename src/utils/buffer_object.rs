@@ -3,6 +3,7 @@ use crate::{gl_call, utils::*};
 pub struct BufferObject {
     m_id: IdType,
     m_target: GLenum,
+    m_usage: GLenum,
 }
 
 impl BufferObject {
@@ -10,21 +11,27 @@ impl BufferObject {
         let mut obj = BufferObject {
             m_id: 0,
             m_target: target,
+            m_usage: usage,
         };
 
         gl_call!(gl::GenBuffers(1, &mut obj.m_id));
 
-        obj.bind();
+        obj.set_data(buffer);
+
+        obj
+    }
+
+    /// Replaces the whole content of the buffer, the size can change.
+    pub fn set_data<T>(&self, buffer: &[T]) {
+        self.bind();
         gl_call!(gl::BufferData(
-            obj.m_target,
+            self.m_target,
             (buffer.len() * mem::size_of::<T>()) as GLsizeiptr,
             buffer.as_ptr() as VoidPtr,
-            usage
+            self.m_usage
         ));
 
-        obj.unbind();
-
-        obj
+        self.unbind();
     }
 
     pub fn bind(&self) {
@@ -53,6 +60,12 @@ impl BufferObject {
         ));
         gl_call!(gl::EnableVertexAttribArray(index));
     }
+
+    /// Makes the attribute advance once every `divisor` instances instead of
+    /// once per vertex.
+    pub fn set_divisor(index: GLuint, divisor: GLuint) {
+        gl_call!(gl::VertexAttribDivisor(index, divisor));
+    }
 }
 
 impl Drop for BufferObject {
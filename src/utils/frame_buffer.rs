@@ -0,0 +1,132 @@
+use super::*;
+
+pub struct FrameBuffer {
+    m_id: IdType,
+    m_render_buffers: Vec<IdType>,
+}
+
+impl FrameBuffer {
+    /// A frame buffer that only writes depth, into `texture`.
+    pub fn new_depth(texture: &Texture) -> Self {
+        let mut obj = FrameBuffer {
+            m_id: 0,
+            m_render_buffers: Vec::new(),
+        };
+
+        gl_call!(gl::GenFramebuffers(1, &mut obj.m_id));
+
+        obj.bind();
+
+        gl_call!(gl::FramebufferTexture2D(
+            gl::FRAMEBUFFER,
+            gl::DEPTH_ATTACHMENT,
+            gl::TEXTURE_2D,
+            texture.id(),
+            0
+        ));
+
+        gl_call!(gl::DrawBuffer(gl::NONE));
+        gl_call!(gl::ReadBuffer(gl::NONE));
+
+        obj.check_status();
+        obj.unbind();
+
+        obj
+    }
+
+    /// A color and depth target to render frames into instead of the window,
+    /// the color can be read back with `read_pixels`.
+    pub fn new_offscreen(width: u32, height: u32) -> Self {
+        let mut obj = FrameBuffer {
+            m_id: 0,
+            m_render_buffers: vec![0; 2],
+        };
+
+        gl_call!(gl::GenFramebuffers(1, &mut obj.m_id));
+        gl_call!(gl::GenRenderbuffers(2, obj.m_render_buffers.as_mut_ptr()));
+
+        obj.bind();
+
+        let attachments = [
+            (gl::RGBA8, gl::COLOR_ATTACHMENT0),
+            (gl::DEPTH_COMPONENT24, gl::DEPTH_ATTACHMENT),
+        ];
+
+        for (id, (format, attachment)) in obj.m_render_buffers.iter().zip(attachments.iter()) {
+            gl_call!(gl::BindRenderbuffer(gl::RENDERBUFFER, *id));
+            gl_call!(gl::RenderbufferStorage(
+                gl::RENDERBUFFER,
+                *format,
+                width as GLsizei,
+                height as GLsizei
+            ));
+            gl_call!(gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                *attachment,
+                gl::RENDERBUFFER,
+                *id
+            ));
+        }
+
+        gl_call!(gl::BindRenderbuffer(gl::RENDERBUFFER, 0));
+
+        obj.check_status();
+        obj.unbind();
+
+        obj
+    }
+
+    fn check_status(&self) {
+        let status;
+
+        gl_call!(status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER));
+
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            panic!("[OpenGL Error]: incomplete frame buffer {}", status);
+        }
+    }
+
+    /// Reads the color attachment as rgba rows, starting from the bottom row.
+    pub fn read_pixels(&self, width: u32, height: u32) -> Vec<u8> {
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+
+        self.bind();
+
+        gl_call!(gl::ReadBuffer(gl::COLOR_ATTACHMENT0));
+        gl_call!(gl::PixelStorei(gl::PACK_ALIGNMENT, 1));
+        gl_call!(gl::ReadPixels(
+            0,
+            0,
+            width as GLsizei,
+            height as GLsizei,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            pixels.as_mut_ptr() as *mut ffi::c_void
+        ));
+
+        self.unbind();
+
+        pixels
+    }
+
+    pub fn bind(&self) {
+        gl_call!(gl::BindFramebuffer(gl::FRAMEBUFFER, self.m_id));
+    }
+
+    pub fn unbind(&self) {
+        gl_call!(gl::BindFramebuffer(gl::FRAMEBUFFER, 0));
+    }
+}
+
+impl Drop for FrameBuffer {
+    fn drop(&mut self) {
+        gl_call!(gl::DeleteFramebuffers(1, &self.m_id));
+
+        if !self.m_render_buffers.is_empty() {
+            gl_call!(gl::DeleteRenderbuffers(
+                self.m_render_buffers.len() as GLsizei,
+                self.m_render_buffers.as_ptr()
+            ));
+        }
+    }
+}
@@ -49,3 +49,6 @@ pub use vertex_array_object::VertexArrayObject;
 
 pub mod texture;
 pub use texture::Texture;
+
+pub mod frame_buffer;
+pub use frame_buffer::FrameBuffer;
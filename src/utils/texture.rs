@@ -78,6 +78,120 @@ impl Texture {
         texture
     }
 
+    /// A texture of rgba `pixels` from the top row, scaled without blurring.
+    pub fn from_rgba(width: u32, height: u32, pixels: &[u8], index: GLenum) -> Self {
+        let mut texture = Texture {
+            m_id: 0,
+            m_index: index,
+        };
+
+        gl_call!(gl::GenTextures(1, &mut texture.m_id));
+
+        texture.bind();
+
+        gl_call!(gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_MIN_FILTER,
+            gl::NEAREST as GLint
+        ));
+        gl_call!(gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_MAG_FILTER,
+            gl::NEAREST as GLint
+        ));
+        gl_call!(gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_WRAP_S,
+            gl::CLAMP_TO_EDGE as GLint
+        ));
+        gl_call!(gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_WRAP_T,
+            gl::CLAMP_TO_EDGE as GLint
+        ));
+
+        // the rows of odd widths aren't 4 byte aligned
+        gl_call!(gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1));
+
+        gl_call!(gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::RGBA8 as GLint,
+            width as GLsizei,
+            height as GLsizei,
+            0,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            pixels.as_ptr() as VoidPtr
+        ));
+
+        texture.unbind();
+
+        texture
+    }
+
+    /// An empty depth texture to render into, everything outside of it reads
+    /// as the farthest depth.
+    pub fn new_depth(width: u32, height: u32, index: GLenum) -> Self {
+        let mut texture = Texture {
+            m_id: 0,
+            m_index: index,
+        };
+
+        gl_call!(gl::GenTextures(1, &mut texture.m_id));
+
+        texture.bind();
+
+        gl_call!(gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_MIN_FILTER,
+            gl::NEAREST as GLint
+        ));
+        gl_call!(gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_MAG_FILTER,
+            gl::NEAREST as GLint
+        ));
+        gl_call!(gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_WRAP_S,
+            gl::CLAMP_TO_BORDER as GLint
+        ));
+        gl_call!(gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_WRAP_T,
+            gl::CLAMP_TO_BORDER as GLint
+        ));
+
+        let border: [GLfloat; 4] = [1.0; 4];
+
+        gl_call!(gl::TexParameterfv(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_BORDER_COLOR,
+            border.as_ptr()
+        ));
+
+        gl_call!(gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::DEPTH_COMPONENT24 as GLint,
+            width as GLsizei,
+            height as GLsizei,
+            0,
+            gl::DEPTH_COMPONENT,
+            gl::FLOAT,
+            std::ptr::null()
+        ));
+
+        texture.unbind();
+
+        texture
+    }
+
+    pub fn id(&self) -> IdType {
+        self.m_id
+    }
+
     pub fn bind(&self) {
         gl_call!(gl::ActiveTexture(gl::TEXTURE0 + self.m_index));
         gl_call!(gl::BindTexture(gl::TEXTURE_2D, self.m_id));
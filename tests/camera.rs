@@ -0,0 +1,291 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use tetris_3d::render::{Camera, CameraMode, CameraPreset, CameraSettings, Pose, Transition};
+use tetris_3d::{Action, ConfigError};
+
+use nalgebra_glm as glm;
+
+fn pose(pos: glm::Vec3, yaw: f32, pitch: f32) -> Pose {
+    Pose {
+        pos,
+        yaw: yaw.to_radians(),
+        pitch: pitch.to_radians(),
+    }
+}
+
+fn assert_close(a: &glm::Vec3, b: &glm::Vec3) {
+    assert!(glm::distance(a, b) < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn transition_eases_from_start_to_target() {
+    let from = pose(glm::vec3(0.0, 3.0, 6.0), -90.0, 0.0);
+    let to = pose(glm::vec3(0.0, 9.0, 0.0), -90.0, -80.0);
+
+    let mut transition = Transition::new(from, to, Duration::from_millis(400));
+
+    let start = transition.advance(Duration::ZERO);
+
+    assert_close(&start.pos, &from.pos);
+    assert_close(&start.direction(), &from.direction());
+
+    // the easing is symmetric, half of the time is half of the way
+    let middle = transition.advance(Duration::from_millis(200));
+
+    assert_close(&middle.pos, &glm::vec3(0.0, 6.0, 3.0));
+    assert!((middle.pitch - (-40.0f32).to_radians()).abs() < 1e-4);
+    assert!(!transition.is_finished());
+
+    assert_eq!(transition.advance(Duration::from_secs(1)), to);
+    assert!(transition.is_finished());
+}
+
+#[test]
+fn views_turn_the_short_way_around() {
+    let from = pose(glm::vec3(0.0, 0.0, 0.0), 170.0, 0.0);
+    let to = pose(glm::vec3(0.0, 0.0, 0.0), -170.0, 0.0);
+
+    let middle = from.interpolate(&to, 0.5);
+
+    assert_close(&middle.direction(), &glm::vec3(-1.0, 0.0, 0.0));
+
+    // looking the other way there is no short way, it still gets there
+    let back = pose(glm::vec3(0.0, 0.0, 0.0), -10.0, 0.0);
+
+    assert_close(&from.interpolate(&back, 1.0).direction(), &back.direction());
+}
+
+#[test]
+fn shipped_settings_are_the_defaults() {
+    assert_eq!(
+        CameraSettings::load("./res/camera.toml").unwrap(),
+        CameraSettings::default()
+    );
+}
+
+#[test]
+fn custom_presets_replace_the_defaults() {
+    let settings = CameraSettings::from_toml(
+        r#"
+        transition = 250
+        invert_y = true
+
+        [[presets]]
+        name = "side"
+        position = [6, 2.5, 0]
+        yaw = 180
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(settings.transition, Duration::from_millis(250));
+    assert!(settings.invert_y);
+    assert_eq!(settings.speed, CameraSettings::default().speed);
+    assert_eq!(
+        settings.presets,
+        vec![CameraPreset::new(
+            "side",
+            glm::vec3(6.0, 2.5, 0.0),
+            180.0,
+            0.0
+        )]
+    );
+}
+
+#[test]
+fn invalid_presets_are_rejected() {
+    let invalid = |source: &str| match CameraSettings::from_toml(source) {
+        Err(ConfigError::InvalidValue(name)) => name,
+        other => panic!("expected an invalid value, got {:?}", other),
+    };
+
+    assert_eq!(invalid("presets = []"), "presets");
+    assert_eq!(invalid("speed = 0"), "speed");
+    assert_eq!(invalid("invert_y = 1"), "invert_y");
+    assert_eq!(
+        invalid("[[presets]]\nname = \"up\"\nposition = [0, 1]"),
+        "up.position"
+    );
+    assert_eq!(
+        invalid("[[presets]]\nname = \"up\"\nposition = [0, 1, 0]\npitch = 90"),
+        "up.pitch"
+    );
+    assert_eq!(invalid("[[presets]]\nname = \"up\""), "up.position");
+
+    assert!(matches!(
+        CameraSettings::from_toml("[[presets]]\nname = \"up\"\nroll = 3"),
+        Err(ConfigError::UnknownSetting(_))
+    ));
+}
+
+/// A free camera at the start of the orbit, on a cylinder of 15 columns.
+fn free_camera(settings: CameraSettings) -> Camera {
+    let mut camera = Camera::new(settings, std::f32::consts::PI * 2.0 / 15.0, 0.0);
+
+    camera.toggle_mode();
+    assert_eq!(camera.mode(), CameraMode::FreeFly);
+
+    camera
+}
+
+#[test]
+fn next_preset_without_presets_stays_put() {
+    let mut camera = Camera::new(
+        CameraSettings {
+            presets: vec![],
+            ..CameraSettings::default()
+        },
+        std::f32::consts::PI * 2.0 / 15.0,
+        0.0,
+    );
+    let (mode, pos) = (camera.mode(), camera.pos());
+
+    camera.next_preset();
+    camera.update(&HashSet::new(), 0.0, Duration::from_secs(1));
+
+    assert_eq!(camera.mode(), mode);
+    assert_close(&camera.pos(), &pos);
+}
+
+fn held(actions: &[Action]) -> HashSet<Action> {
+    actions.iter().copied().collect()
+}
+
+#[test]
+fn movement_does_not_depend_on_the_frame_rate() {
+    let forward = held(&[Action::CameraForward, Action::CameraLeft]);
+
+    let mut slow = free_camera(CameraSettings::default());
+    let mut fast = free_camera(CameraSettings::default());
+
+    for _ in 0..3 {
+        slow.update(&forward, 0.0, Duration::from_millis(100));
+    }
+
+    for _ in 0..30 {
+        fast.update(&forward, 0.0, Duration::from_millis(10));
+    }
+
+    assert_close(&slow.pos(), &fast.pos());
+}
+
+#[test]
+fn camera_speeds_up_and_glides_to_a_stop() {
+    let settings = CameraSettings::default();
+    let speed = settings.speed;
+
+    let mut camera = free_camera(settings);
+    let start = camera.pos();
+
+    let step = |camera: &mut Camera, actions: &[Action]| {
+        let before = camera.pos();
+
+        camera.update(&held(actions), 0.0, Duration::from_millis(100));
+
+        glm::distance(&before, &camera.pos())
+    };
+
+    let first = step(&mut camera, &[Action::CameraForward]);
+
+    for _ in 0..20 {
+        step(&mut camera, &[Action::CameraForward]);
+    }
+
+    // it starts slow and gets close to the top speed
+    let top = step(&mut camera, &[Action::CameraForward]);
+
+    assert!(first < top);
+    assert!((top - speed * 0.1).abs() < 1e-3);
+
+    // it keeps going for a bit after the key is let go
+    let glide = step(&mut camera, &[]);
+
+    assert!(glide > 0.0 && glide < top);
+
+    for _ in 0..50 {
+        step(&mut camera, &[]);
+    }
+
+    assert!(step(&mut camera, &[]) < 1e-4);
+
+    // forward is -z at the start
+    assert!(camera.pos().z < start.z);
+}
+
+#[test]
+fn invert_y_flips_the_mouse() {
+    let mouse_up = |invert_y| {
+        let mut camera = free_camera(CameraSettings {
+            invert_y,
+            ..CameraSettings::default()
+        });
+
+        camera.look_at(glm::vec2(100.0, 100.0));
+        camera.look_at(glm::vec2(100.0, 80.0));
+
+        camera.pose().pitch
+    };
+
+    assert!(mouse_up(false) > 0.0);
+    assert!(mouse_up(true) < 0.0);
+}
+
+#[test]
+fn scrolling_zooms_the_orbit_within_limits() {
+    let mut camera = Camera::new(CameraSettings::default(), 0.5, 0.0);
+
+    let distance = |camera: &Camera| glm::length(&camera.pos().xz());
+
+    let start = distance(&camera);
+
+    camera.scroll(2.0);
+    assert!((distance(&camera) - (start - 1.0)).abs() < 1e-4);
+
+    camera.scroll(-100.0);
+    let far = distance(&camera);
+
+    camera.scroll(-1.0);
+    assert_eq!(distance(&camera), far);
+}
+
+#[test]
+fn orbit_follows_the_piece_column() {
+    let mut camera = Camera::new(CameraSettings::default(), 0.5, 0.0);
+
+    camera.update(&HashSet::new(), 4.0, Duration::from_millis(50));
+
+    let column = camera.orbit_column();
+
+    assert!(column > 0.0 && column < 4.0);
+
+    camera.update(&HashSet::new(), 4.0, Duration::from_secs(5));
+
+    assert!((camera.orbit_column() - 4.0).abs() < 1e-4);
+}
+
+#[test]
+fn turning_moves_the_orbit_around_the_piece() {
+    let sector_angle = std::f32::consts::PI * 2.0 / 15.0;
+    let mut camera = Camera::new(CameraSettings::default(), sector_angle, 0.0);
+
+    let start = camera.pos();
+
+    // looking right and down takes the orbit left and up
+    camera.turn(glm::vec2(sector_angle * 2.0, -0.2));
+
+    assert_eq!(camera.mode(), CameraMode::Orbit);
+    assert!((camera.orbit_column() + 2.0).abs() < 1e-4);
+    assert!(camera.pos().y > start.y);
+    assert!(camera.pos().x < start.x);
+
+    // it keeps the offset while following the piece
+    camera.update(&HashSet::new(), 3.0, Duration::from_secs(5));
+
+    assert!((camera.orbit_column() - 1.0).abs() < 1e-4);
+
+    // the distance to the axis it looks at doesn't change
+    let distance = |pos: glm::Vec3| glm::length(&(pos - glm::vec3(0.0, start.y, 0.0)));
+
+    assert!((distance(camera.pos()) - distance(start)).abs() < 1e-4);
+}
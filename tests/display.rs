@@ -0,0 +1,104 @@
+use tetris_3d::render::display::{MAX_FOV, MIN_FOV};
+use tetris_3d::render::DisplaySettings;
+use tetris_3d::ConfigError;
+
+#[test]
+fn shipped_settings_are_the_defaults() {
+    assert_eq!(
+        DisplaySettings::load("./res/display.toml").unwrap(),
+        DisplaySettings::default()
+    );
+}
+
+#[test]
+fn saved_settings_load_back_over_the_shipped_ones() {
+    let shipped = DisplaySettings {
+        fov: 50.0,
+        ..DisplaySettings::default()
+    };
+
+    let settings = DisplaySettings {
+        fullscreen: true,
+        borderless: false,
+        ..shipped
+    };
+
+    // the directory doesn't exist yet
+    let dir = std::env::temp_dir().join("tetris-3d-display-test");
+    let file_path = dir.join("display.toml");
+
+    settings.save_over(&shipped, &file_path).unwrap();
+
+    let saved = std::fs::read_to_string(&file_path).unwrap();
+    let loaded = shipped.load_over(&file_path);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(loaded.unwrap(), settings);
+
+    // only the changes are kept, the fov still comes from the shipped file
+    assert!(!saved.contains("fov"));
+    assert_eq!(
+        DisplaySettings::from_toml(&saved).unwrap().fov,
+        DisplaySettings::default().fov
+    );
+}
+
+#[test]
+fn settings_like_the_shipped_ones_save_nothing() {
+    let shipped = DisplaySettings::default();
+
+    assert_eq!(shipped.overrides_of(&shipped), "");
+
+    let changed = DisplaySettings {
+        fov: 72.5,
+        ..shipped
+    };
+
+    assert_eq!(
+        DisplaySettings::from_toml(&changed.overrides_of(&shipped)).unwrap(),
+        changed
+    );
+}
+
+#[test]
+fn settings_left_out_keep_their_defaults() {
+    let settings = DisplaySettings::from_toml("fullscreen = true").unwrap();
+
+    assert!(settings.fullscreen);
+    assert_eq!(settings.fov, DisplaySettings::default().fov);
+    assert_eq!(settings.borderless, DisplaySettings::default().borderless);
+
+    assert_eq!(
+        DisplaySettings::load("./res/missing.toml").unwrap(),
+        DisplaySettings::default()
+    );
+
+    let shipped = DisplaySettings {
+        fov: 90.0,
+        ..DisplaySettings::default()
+    };
+
+    assert_eq!(shipped.load_over("./res/missing.toml").unwrap(), shipped);
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    let invalid = |source: &str| match DisplaySettings::from_toml(source) {
+        Err(ConfigError::InvalidValue(name)) => name,
+        other => panic!("expected an invalid value, got {:?}", other),
+    };
+
+    assert_eq!(invalid(&format!("fov = {}", MIN_FOV - 1.0)), "fov");
+    assert_eq!(invalid(&format!("fov = {}", MAX_FOV + 1.0)), "fov");
+    assert_eq!(invalid("fov = \"wide\""), "fov");
+    assert_eq!(invalid("fullscreen = 1"), "fullscreen");
+
+    assert!(matches!(
+        DisplaySettings::from_toml("vsync = true"),
+        Err(ConfigError::UnknownSetting(_))
+    ));
+
+    // whole degrees work too
+    assert_eq!(DisplaySettings::from_toml("fov = 90").unwrap().fov, 90.0);
+}
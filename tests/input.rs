@@ -0,0 +1,369 @@
+use std::time::Duration;
+
+use tetris_3d::gamepad::Button;
+use tetris_3d::input::CHAR_KEYS;
+use tetris_3d::{
+    Action, Command, ConfigError, Controller, GameEvent, GameLogic, GameState, Gamepad,
+    GamepadSettings, Handling, Key, KeyBindings, PadState, Rotation,
+};
+
+#[test]
+fn letters_map_regardless_of_case() {
+    let bindings = KeyBindings::default();
+
+    for c in ['x', 'X'] {
+        assert_eq!(
+            bindings.command_for(Key::Char(c)),
+            Some(Command::Rotate(Rotation::Clockwise))
+        );
+    }
+
+    assert_eq!(bindings.command_for(Key::Char('q')), None);
+}
+
+#[test]
+fn file_overrides_only_the_actions_it_names() {
+    let bindings = KeyBindings::from_toml(
+        r#"
+        move_left = ["a", "left"]
+        camera_left = []
+        pause = "Escape"
+        quit = "q"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(bindings.action_for(Key::Char('a')), Some(Action::MoveLeft));
+    assert_eq!(bindings.action_for(Key::Left), Some(Action::MoveLeft));
+    assert_eq!(bindings.action_for(Key::Escape), Some(Action::Pause));
+    assert_eq!(bindings.keys_for(Action::CameraLeft), &[]);
+
+    assert_eq!(
+        bindings.keys_for(Action::Hold),
+        &[Key::Char('c'), Key::LeftShift]
+    );
+}
+
+#[test]
+fn invalid_files_are_rejected() {
+    // the default hold key is still c
+    match KeyBindings::from_toml(r#"rotate_ccw = ["z", "C"]"#) {
+        Err(ConfigError::Conflict { key, actions }) => {
+            assert_eq!(key, Key::Char('c'));
+            assert_eq!(actions, (Action::RotateCcw, Action::Hold));
+        }
+        other => panic!("expected a conflict, got {:?}", other),
+    }
+
+    assert!(matches!(
+        KeyBindings::from_toml(r#"jump = "space""#),
+        Err(ConfigError::UnknownAction(_))
+    ));
+    assert!(matches!(
+        KeyBindings::from_toml(r#"hold = "caps_lock""#),
+        Err(ConfigError::UnknownKey {
+            action: Action::Hold,
+            ..
+        })
+    ));
+    assert!(matches!(
+        KeyBindings::from_toml("hold = 3"),
+        Err(ConfigError::NotAKeyList(Action::Hold))
+    ));
+    assert!(matches!(
+        KeyBindings::from_toml("hold = ["),
+        Err(ConfigError::Parse(_))
+    ));
+}
+
+#[test]
+fn only_keys_the_front_ends_report_can_be_bound() {
+    for c in CHAR_KEYS.chars() {
+        let key: Key = c.to_string().parse().unwrap();
+
+        assert_eq!(key, Key::Char(c));
+        assert_eq!(key.to_string(), c.to_string());
+    }
+
+    let bindings = KeyBindings::from_toml(r#"hold = ["1", ","]"#).unwrap();
+
+    assert_eq!(bindings.action_for(Key::Char('1')), Some(Action::Hold));
+    assert_eq!(bindings.action_for(Key::Char(',')), Some(Action::Hold));
+
+    // shifted symbols and keys missing from a us keyboard never come in
+    for key in ["!", "?", "é", "ß", "\t"] {
+        assert!(
+            matches!(
+                KeyBindings::from_toml(&format!(r#"hold = "{}""#, key)),
+                Err(ConfigError::UnknownKey {
+                    action: Action::Hold,
+                    ..
+                })
+            ),
+            "{}",
+            key
+        );
+    }
+}
+
+#[test]
+fn shipped_file_is_the_defaults() {
+    assert_eq!(
+        KeyBindings::load("./res/bindings.toml").unwrap(),
+        KeyBindings::default()
+    );
+}
+
+#[test]
+fn confirm_starts_and_restarts() {
+    let mut game = GameLogic::with_seed(10, 20, 0);
+
+    Command::Confirm.apply(&mut game);
+    assert_eq!(game.state(), GameState::Playing);
+
+    // a second confirm while playing does nothing
+    Command::Confirm.apply(&mut game);
+    assert_eq!(game.state(), GameState::Playing);
+
+    while game.state() != GameState::GameOver {
+        Command::HardDrop.apply(&mut game);
+    }
+
+    Command::Confirm.apply(&mut game);
+    assert_eq!(game.state(), GameState::Playing);
+    assert_eq!(game.stats().score, 0);
+}
+
+fn handling(das: u64, arr: u64) -> Handling {
+    Handling {
+        das: Duration::from_millis(das),
+        arr: Duration::from_millis(arr),
+        ..Handling::default()
+    }
+}
+
+fn started_game() -> GameLogic {
+    let mut game = GameLogic::with_seed(15, 20, 0);
+
+    game.start();
+    game
+}
+
+#[test]
+fn held_moves_repeat_after_das_every_arr() {
+    let mut game = started_game();
+    let mut controller = Controller::new(handling(100, 20));
+
+    let x = game.current_piece().pos.x;
+
+    controller.press(Action::MoveRight, &mut game);
+    assert_eq!(game.current_piece().pos.x, x + 1);
+
+    controller.update(Duration::from_millis(99), &mut game);
+    assert_eq!(game.current_piece().pos.x, x + 1);
+
+    // one move when the delay runs out, then one every 20ms, however the
+    // time is split
+    controller.update(Duration::from_millis(1), &mut game);
+    assert_eq!(game.current_piece().pos.x, x + 2);
+
+    for _ in 0..4 {
+        controller.update(Duration::from_millis(10), &mut game);
+    }
+    assert_eq!(game.current_piece().pos.x, x + 4);
+
+    controller.release(Action::MoveRight, &mut game);
+    controller.update(Duration::from_secs(1), &mut game);
+    assert_eq!(game.current_piece().pos.x, x + 4);
+}
+
+#[test]
+fn zero_arr_shifts_once_per_press() {
+    let mut game = started_game();
+    let mut controller = Controller::new(handling(100, 0));
+
+    controller.press(Action::MoveRight, &mut game);
+    controller.update(Duration::from_millis(100), &mut game);
+
+    let x = game.current_piece().pos.x;
+    let moves = |game: &mut GameLogic| {
+        game.drain_events()
+            .filter(|event| matches!(event, GameEvent::Moved { .. }))
+            .count()
+    };
+
+    assert!(moves(&mut game) > 1);
+
+    // still held, the piece stays put instead of going around every frame
+    for _ in 0..4 {
+        controller.update(Duration::from_millis(100), &mut game);
+    }
+    assert_eq!(game.current_piece().pos.x, x);
+    assert_eq!(moves(&mut game), 0);
+
+    controller.release(Action::MoveRight, &mut game);
+    controller.press(Action::MoveRight, &mut game);
+    controller.update(Duration::from_millis(100), &mut game);
+    assert!(moves(&mut game) > 1);
+}
+
+#[test]
+fn last_pressed_direction_wins() {
+    let mut game = started_game();
+    let mut controller = Controller::new(handling(100, 50));
+
+    let x = game.current_piece().pos.x;
+
+    controller.press(Action::MoveLeft, &mut game);
+    controller.update(Duration::from_millis(100), &mut game);
+    assert_eq!(game.current_piece().pos.x, x - 2);
+
+    controller.press(Action::MoveRight, &mut game);
+    controller.update(Duration::from_millis(100), &mut game);
+    assert_eq!(game.current_piece().pos.x, x);
+
+    // the left key is still down, it charges the delay again
+    controller.release(Action::MoveRight, &mut game);
+    controller.update(Duration::from_millis(99), &mut game);
+    assert_eq!(game.current_piece().pos.x, x);
+
+    controller.update(Duration::from_millis(1), &mut game);
+    assert_eq!(game.current_piece().pos.x, x - 1);
+}
+
+#[test]
+fn soft_drop_uses_the_handling_factor() {
+    let mut game = started_game();
+    let mut controller = Controller::new(Handling {
+        soft_drop_factor: 20,
+        ..Handling::default()
+    });
+
+    let y = game.current_piece().pos.y;
+
+    controller.press(Action::SoftDrop, &mut game);
+    assert_eq!(game.soft_drop_factor(), 20);
+
+    // level 1 gravity is a second per row
+    game.update(Duration::from_millis(50));
+    assert_eq!(game.current_piece().pos.y, y + 1);
+
+    controller.release(Action::SoftDrop, &mut game);
+    game.update(Duration::from_millis(50));
+    assert_eq!(game.current_piece().pos.y, y + 1);
+}
+
+#[test]
+fn handling_file_overrides_only_the_settings_it_names() {
+    assert_eq!(
+        Handling::from_toml("das = 120\narr = 0").unwrap(),
+        handling(120, 0)
+    );
+    assert_eq!(
+        Handling::load("./res/handling.toml").unwrap(),
+        Handling::default()
+    );
+
+    assert!(matches!(
+        Handling::from_toml("soft_drop_factor = 0"),
+        Err(ConfigError::InvalidValue(_))
+    ));
+    assert!(matches!(
+        Handling::from_toml("das = -1"),
+        Err(ConfigError::InvalidValue(_))
+    ));
+    assert!(matches!(
+        Handling::from_toml("dcd = 10"),
+        Err(ConfigError::UnknownSetting(_))
+    ));
+}
+
+fn pad(buttons: &[Button]) -> PadState {
+    let mut state = PadState::default();
+
+    for &button in buttons {
+        state.buttons[button as usize] = true;
+    }
+
+    state
+}
+
+#[test]
+fn gamepad_reports_only_changes() {
+    let mut gamepad = Gamepad::new(0.2);
+
+    assert_eq!(
+        gamepad.poll(&pad(&[Button::DpadLeft, Button::A])),
+        vec![(Action::MoveLeft, true), (Action::RotateCcw, true)]
+    );
+    assert_eq!(gamepad.poll(&pad(&[Button::DpadLeft, Button::A])), vec![]);
+
+    // a second button of the same action doesn't press it again
+    assert_eq!(
+        gamepad.poll(&pad(&[Button::DpadLeft, Button::X, Button::LeftBumper])),
+        vec![(Action::RotateCcw, false), (Action::Hold, true)]
+    );
+
+    assert_eq!(gamepad.disconnect(), vec![Action::MoveLeft, Action::Hold]);
+    assert_eq!(gamepad.poll(&pad(&[])), vec![]);
+}
+
+#[test]
+fn sticks_ignore_the_deadzone() {
+    let mut gamepad = Gamepad::new(0.2);
+
+    let mut state = PadState {
+        left_stick: (-0.15, 0.1),
+        right_stick: (0.1, 0.1),
+        ..PadState::default()
+    };
+
+    assert_eq!(gamepad.poll(&state), vec![]);
+    assert_eq!(gamepad.right_stick(), (0.0, 0.0));
+
+    state.left_stick = (-0.5, 0.1);
+    state.right_stick = (0.0, 0.6);
+
+    assert_eq!(gamepad.poll(&state), vec![(Action::MoveLeft, true)]);
+    assert!((gamepad.right_stick().1 - 0.5).abs() < 1e-6);
+
+    // pushed down it soft drops, up does nothing
+    state.left_stick = (0.1, 0.9);
+    assert_eq!(
+        gamepad.poll(&state),
+        vec![(Action::MoveLeft, false), (Action::SoftDrop, true)]
+    );
+
+    state.left_stick = (0.1, -0.9);
+    assert_eq!(gamepad.poll(&state), vec![(Action::SoftDrop, false)]);
+}
+
+#[test]
+fn devices_can_have_their_own_deadzone() {
+    let settings = GamepadSettings::from_toml(
+        r#"
+        deadzone = 0.1
+
+        [devices."Worn Pad"]
+        deadzone = 0.35
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(settings.deadzone_for("Worn Pad"), 0.35);
+    assert_eq!(settings.deadzone_for("Xbox Controller"), 0.1);
+
+    assert_eq!(
+        GamepadSettings::load("./res/gamepad.toml").unwrap(),
+        GamepadSettings::default()
+    );
+
+    assert!(matches!(
+        GamepadSettings::from_toml("deadzone = 1.0"),
+        Err(ConfigError::InvalidValue(_))
+    ));
+    assert!(matches!(
+        GamepadSettings::from_toml("[devices.pad]\nsensitivity = 2"),
+        Err(ConfigError::UnknownSetting(_))
+    ));
+}
@@ -0,0 +1,29 @@
+use tetris_3d::{BagGenerator, PieceGenerator, TETROMINO_COORDS};
+
+fn deal(generator: &mut impl PieceGenerator, count: usize) -> Vec<usize> {
+    (0..count).map(|_| generator.next_piece()).collect()
+}
+
+#[test]
+fn every_bag_deals_each_piece_once() {
+    let pieces = TETROMINO_COORDS.len();
+
+    for seed in 0..20 {
+        let dealt = deal(&mut BagGenerator::new(seed), pieces * 50);
+
+        for bag in dealt.chunks(pieces) {
+            let mut bag = bag.to_vec();
+
+            bag.sort_unstable();
+            assert_eq!(bag, (0..pieces).collect::<Vec<_>>(), "seed {}", seed);
+        }
+    }
+}
+
+#[test]
+fn same_seed_deals_the_same_pieces() {
+    let dealt = deal(&mut BagGenerator::new(42), 100);
+
+    assert_eq!(deal(&mut BagGenerator::new(42), 100), dealt);
+    assert_ne!(deal(&mut BagGenerator::new(43), 100), dealt);
+}
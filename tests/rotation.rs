@@ -0,0 +1,403 @@
+use std::time::Duration;
+
+use tetris_3d::game_logic::{Vec2, I_TETROMINO, O_TETROMINO, T_TETROMINO};
+use tetris_3d::game_logic::{J_TETROMINO, L_TETROMINO, S_TETROMINO, Z_TETROMINO};
+use tetris_3d::{GameLogic, Rotation, SequenceGenerator};
+
+const WIDTH: usize = 10;
+const HEIGHT: usize = 20;
+
+/// Starts a game that only deals `piece_type` and lowers the first piece
+/// until its pivot is on row `y`.
+fn game_with(piece_type: usize, y: i32) -> GameLogic {
+    let generator = SequenceGenerator::new(vec![piece_type]);
+    let mut game = GameLogic::with_generator(WIDTH, HEIGHT, Box::new(generator));
+
+    game.start();
+
+    let start_y = game.current_piece().pos.y;
+
+    // level 1 gravity moves the piece one row per second
+    game.update(Duration::from_secs((y - start_y) as u64));
+
+    assert_eq!(game.current_piece().pos.y, y);
+
+    game
+}
+
+/// Grid cells of the current piece, sorted and wrapped around the cylinder.
+fn blocks(game: &GameLogic) -> Vec<Vec2> {
+    let piece = game.current_piece();
+
+    let mut blocks: Vec<Vec2> = (0..4)
+        .map(|i| {
+            let pos = piece.get_pos_at(i);
+
+            Vec2::new(pos.x.rem_euclid(WIDTH as i32), pos.y)
+        })
+        .collect();
+
+    blocks.sort_by_key(|v| (v.y, v.x));
+    blocks
+}
+
+/// The four SRS rotation states of the pieces in a 3x3 box, clockwise from
+/// spawn. The pivot is the centre of the box.
+const BOXES: [(usize, [[&str; 3]; 4]); 5] = [
+    (
+        J_TETROMINO,
+        [
+            ["#..", "###", "..."],
+            [".##", ".#.", ".#."],
+            ["...", "###", "..#"],
+            [".#.", ".#.", "##."],
+        ],
+    ),
+    (
+        L_TETROMINO,
+        [
+            ["..#", "###", "..."],
+            [".#.", ".#.", ".##"],
+            ["...", "###", "#.."],
+            ["##.", ".#.", ".#."],
+        ],
+    ),
+    (
+        S_TETROMINO,
+        [
+            [".##", "##.", "..."],
+            [".#.", ".##", "..#"],
+            ["...", ".##", "##."],
+            ["#..", "##.", ".#."],
+        ],
+    ),
+    (
+        T_TETROMINO,
+        [
+            [".#.", "###", "..."],
+            [".#.", ".##", ".#."],
+            ["...", "###", ".#."],
+            [".#.", "##.", ".#."],
+        ],
+    ),
+    (
+        Z_TETROMINO,
+        [
+            ["##.", ".##", "..."],
+            ["..#", ".##", ".#."],
+            ["...", "##.", ".##"],
+            [".#.", "##.", "#.."],
+        ],
+    ),
+];
+
+/// The cells of rotation `state` of `piece_type` with the box centred on
+/// `pivot`, sorted like `blocks`.
+fn box_cells(piece_type: usize, state: usize, pivot: Vec2) -> Vec<Vec2> {
+    let (_, states) = BOXES
+        .iter()
+        .find(|(it, _)| *it == piece_type)
+        .expect("the piece has a box");
+
+    let mut cells = vec![];
+
+    for (dy, row) in states[state].iter().enumerate() {
+        for (dx, cell) in row.chars().enumerate() {
+            if cell == '#' {
+                let pos = pivot + Vec2::new(dx as i32 - 1, dy as i32 - 1);
+
+                cells.push(Vec2::new(pos.x.rem_euclid(WIDTH as i32), pos.y));
+            }
+        }
+    }
+
+    cells.sort_by_key(|v| (v.y, v.x));
+    cells
+}
+
+fn fill_row(game: &mut GameLogic, y: i32) {
+    for x in 0..WIDTH as i32 {
+        game.set_grid(Vec2::new(x, y), 0);
+    }
+}
+
+#[test]
+fn four_clockwise_rotations_return_to_spawn() {
+    for piece_type in (0..7).filter(|&t| t != O_TETROMINO) {
+        let mut game = game_with(piece_type, 8);
+        let spawn = blocks(&game);
+
+        for _ in 0..4 {
+            assert_eq!(game.rotate_piece(Rotation::Clockwise), Some(0));
+        }
+
+        assert_eq!(game.current_piece().rotation, 0);
+        assert_eq!(blocks(&game), spawn, "piece {}", piece_type);
+    }
+}
+
+#[test]
+fn counter_clockwise_undoes_clockwise() {
+    for piece_type in (0..7).filter(|&t| t != O_TETROMINO) {
+        let mut game = game_with(piece_type, 8);
+        let spawn = blocks(&game);
+
+        game.rotate_piece(Rotation::Clockwise);
+        assert_eq!(game.current_piece().rotation, 1);
+
+        game.rotate_piece(Rotation::CounterClockwise);
+        assert_eq!(game.current_piece().rotation, 0);
+
+        assert_eq!(blocks(&game), spawn, "piece {}", piece_type);
+    }
+}
+
+#[test]
+fn half_rotation_matches_two_quarter_turns() {
+    for piece_type in (0..7).filter(|&t| t != O_TETROMINO) {
+        let mut half = game_with(piece_type, 8);
+        let mut quarters = game_with(piece_type, 8);
+
+        assert_eq!(half.rotate_piece(Rotation::Half), Some(0));
+
+        quarters.rotate_piece(Rotation::Clockwise);
+        quarters.rotate_piece(Rotation::Clockwise);
+
+        assert_eq!(half.current_piece().rotation, 2);
+        assert_eq!(blocks(&half), blocks(&quarters), "piece {}", piece_type);
+    }
+}
+
+#[test]
+fn i_piece_rotates_inside_its_box() {
+    let mut game = game_with(I_TETROMINO, 8);
+    let pivot = game.current_piece().pos;
+
+    // spawn state is the second row of the 4x4 box, the pivot is its second
+    // cell from the left
+    let column = |x: i32| -> Vec<Vec2> {
+        (pivot.y - 1..=pivot.y + 2)
+            .map(|y| Vec2::new(x, y))
+            .collect()
+    };
+    let row = |y: i32| -> Vec<Vec2> {
+        (pivot.x - 1..=pivot.x + 2)
+            .map(|x| Vec2::new(x, y))
+            .collect()
+    };
+
+    game.rotate_piece(Rotation::Clockwise);
+    assert_eq!(blocks(&game), column(pivot.x + 1));
+
+    game.rotate_piece(Rotation::Clockwise);
+    assert_eq!(blocks(&game), row(pivot.y + 1));
+
+    game.rotate_piece(Rotation::Clockwise);
+    assert_eq!(blocks(&game), column(pivot.x));
+
+    game.rotate_piece(Rotation::Clockwise);
+    assert_eq!(blocks(&game), row(pivot.y));
+}
+
+#[test]
+fn o_piece_does_not_rotate() {
+    let mut game = game_with(O_TETROMINO, 8);
+    let spawn = blocks(&game);
+
+    assert_eq!(game.rotate_piece(Rotation::Clockwise), None);
+    assert_eq!(game.rotate_piece(Rotation::Half), None);
+
+    assert_eq!(blocks(&game), spawn);
+}
+
+#[test]
+fn t_piece_kicks_away_from_the_stack() {
+    let mut game = game_with(T_TETROMINO, 5);
+    let pivot = game.current_piece().pos;
+
+    // right under the pivot, where the clockwise T points to
+    game.set_grid(pivot + Vec2::new(0, 1), 0);
+
+    assert_eq!(game.rotate_piece(Rotation::Clockwise), Some(1));
+    assert_eq!(game.current_piece().pos, pivot + Vec2::new(-1, 0));
+}
+
+#[test]
+fn t_piece_kicks_up_off_the_floor() {
+    let mut game = game_with(T_TETROMINO, 5);
+    let pivot = game.current_piece().pos;
+
+    fill_row(&mut game, pivot.y + 1);
+
+    assert_eq!(game.rotate_piece(Rotation::Clockwise), Some(2));
+    assert_eq!(game.current_piece().pos, pivot + Vec2::new(-1, -1));
+}
+
+#[test]
+fn kicks_wrap_around_the_cylinder() {
+    let mut game = game_with(T_TETROMINO, 5);
+
+    game.move_piece(-game.current_piece().pos.x);
+
+    let pivot = game.current_piece().pos;
+    assert_eq!(pivot.x, 0);
+
+    game.set_grid(pivot + Vec2::new(0, 1), 0);
+
+    // there is no wall on the left, the kick moves the piece to the last
+    // column instead
+    assert_eq!(game.rotate_piece(Rotation::Clockwise), Some(1));
+
+    let columns: Vec<i32> = blocks(&game).iter().map(|v| v.x).collect();
+
+    assert!(columns.contains(&(WIDTH as i32 - 1)));
+    assert!(!columns.contains(&1));
+}
+
+#[test]
+fn failed_rotation_leaves_the_piece_alone() {
+    let mut game = game_with(T_TETROMINO, 8);
+    let spawn = blocks(&game);
+
+    for y in 0..HEIGHT as i32 {
+        for x in 0..WIDTH as i32 {
+            if !spawn.contains(&Vec2::new(x, y)) {
+                game.set_grid(Vec2::new(x, y), 0);
+            }
+        }
+    }
+
+    for rotation in [
+        Rotation::Clockwise,
+        Rotation::CounterClockwise,
+        Rotation::Half,
+    ] {
+        assert_eq!(game.rotate_piece(rotation), None);
+        assert_eq!(game.current_piece().rotation, 0);
+        assert_eq!(blocks(&game), spawn);
+    }
+}
+
+#[test]
+fn jlstz_pieces_rotate_inside_their_box() {
+    for &(piece_type, _) in &BOXES {
+        let mut game = game_with(piece_type, 8);
+        let pivot = game.current_piece().pos;
+
+        assert_eq!(blocks(&game), box_cells(piece_type, 0, pivot));
+
+        for state in &[1, 2, 3, 0] {
+            assert_eq!(game.rotate_piece(Rotation::Clockwise), Some(0));
+            assert_eq!(
+                blocks(&game),
+                box_cells(piece_type, *state, pivot),
+                "piece {} state {}",
+                piece_type,
+                state
+            );
+        }
+
+        for state in &[3, 2, 1, 0] {
+            assert_eq!(game.rotate_piece(Rotation::CounterClockwise), Some(0));
+            assert_eq!(blocks(&game), box_cells(piece_type, *state, pivot));
+        }
+    }
+}
+
+#[test]
+fn every_piece_spawns_with_its_top_row_on_the_spawn_row() {
+    let spawned = |piece_type| {
+        let generator = SequenceGenerator::new(vec![piece_type]);
+        let mut game = GameLogic::with_generator(WIDTH, HEIGHT, Box::new(generator));
+
+        game.start();
+
+        let first = game.current_piece().pos.y;
+
+        // the first piece spawns like the ones after it, a restart too
+        game.hard_drop_piece();
+        assert_eq!(game.current_piece().pos.y, first, "piece {}", piece_type);
+
+        game.restart();
+        game.start();
+        assert_eq!(game.current_piece().pos.y, first, "piece {}", piece_type);
+
+        game
+    };
+
+    // the flat I sits on its pivot row
+    let spawn_row = spawned(I_TETROMINO).current_piece().pos.y;
+
+    for piece_type in 0..7 {
+        let game = spawned(piece_type);
+
+        assert_eq!(blocks(&game)[0].y, spawn_row, "piece {}", piece_type);
+    }
+
+    // the pieces with a cell above their pivot start one row lower
+    for &piece_type in &[
+        J_TETROMINO,
+        L_TETROMINO,
+        S_TETROMINO,
+        T_TETROMINO,
+        Z_TETROMINO,
+    ] {
+        assert_eq!(
+            spawned(piece_type).current_piece().pos.y,
+            spawn_row + 1,
+            "piece {}",
+            piece_type
+        );
+    }
+
+    assert_eq!(spawned(O_TETROMINO).current_piece().pos.y, spawn_row);
+}
+
+#[test]
+fn jlsz_pieces_kick_past_a_block() {
+    for &piece_type in &[J_TETROMINO, L_TETROMINO, S_TETROMINO, Z_TETROMINO] {
+        let mut game = game_with(piece_type, 8);
+        let pivot = game.current_piece().pos;
+
+        // a cell of the clockwise state that is free once the piece moves
+        // one column left, the second test of 0->R
+        let spawn = box_cells(piece_type, 0, pivot);
+        let kicked = box_cells(piece_type, 1, pivot + Vec2::new(-1, 0));
+
+        let block = *box_cells(piece_type, 1, pivot)
+            .iter()
+            .find(|cell| !spawn.contains(cell) && !kicked.contains(cell))
+            .expect("some cell only the unkicked rotation covers");
+
+        game.set_grid(block, 0);
+
+        assert_eq!(game.rotate_piece(Rotation::Clockwise), Some(1));
+        assert_eq!(blocks(&game), kicked, "piece {}", piece_type);
+    }
+}
+
+#[test]
+fn jlsz_pieces_kick_up_off_the_floor() {
+    for &piece_type in &[J_TETROMINO, L_TETROMINO, S_TETROMINO, Z_TETROMINO] {
+        // the third tests of 0->R and 0->L go up one row
+        for &(rotation, state, shift) in &[
+            (Rotation::Clockwise, 1, Vec2::new(-1, -1)),
+            (Rotation::CounterClockwise, 3, Vec2::new(1, -1)),
+        ] {
+            let mut game = game_with(piece_type, 5);
+            let pivot = game.current_piece().pos;
+
+            fill_row(&mut game, pivot.y + 1);
+
+            assert_eq!(game.rotate_piece(rotation), Some(2));
+            assert_eq!(game.current_piece().pos, pivot + shift);
+            assert_eq!(
+                blocks(&game),
+                box_cells(piece_type, state, pivot + shift),
+                "piece {} state {}",
+                piece_type,
+                state
+            );
+        }
+    }
+}
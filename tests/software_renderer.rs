@@ -0,0 +1,172 @@
+use tetris_3d::render::renderer::DEFAULT_FOV;
+use tetris_3d::render::{scene, Frame, Light, Renderer, SoftwareRenderer};
+use tetris_3d::GameLogic;
+
+use nalgebra_glm as glm;
+
+const SIZE: (u32, u32) = (96, 64);
+
+/// The starting camera of the window front-end, orbiting in front of the
+/// current piece.
+fn frame(game: &GameLogic) -> Frame {
+    let column = game.current_piece().pos.x as f32;
+    let angle = column * scene::sector_angle(game);
+
+    let eye = glm::vec3(angle.sin() * 6.0, 3.0, angle.cos() * 6.0);
+
+    Frame {
+        view: glm::look_at(&eye, &glm::vec3(0.0, 3.0, 0.0), &glm::vec3(0.0, 1.0, 0.0)),
+        eye,
+        size: SIZE,
+        fov: DEFAULT_FOV,
+        time: 0.0,
+        column_offset: 0.0,
+        front_column: column,
+    }
+}
+
+fn render(game: &GameLogic) -> Vec<u8> {
+    let mut renderer = SoftwareRenderer::new(game, Light::default());
+
+    renderer.draw(game, &frame(game));
+
+    assert_eq!(renderer.size(), SIZE);
+
+    renderer.pixels().to_vec()
+}
+
+fn started_game(seed: u64) -> GameLogic {
+    let mut game = GameLogic::with_seed(15, 20, seed);
+
+    game.start();
+    game
+}
+
+fn sky_pixel() -> Vec<u8> {
+    scene::SKY_COLOR
+        .iter()
+        .map(|c| (c * 255.0).round() as u8)
+        .chain(Some(u8::MAX))
+        .collect()
+}
+
+#[test]
+fn output_is_deterministic() {
+    assert_eq!(render(&started_game(3)), render(&started_game(3)));
+}
+
+#[test]
+fn frame_has_the_requested_size() {
+    let pixels = render(&started_game(0));
+
+    assert_eq!(pixels.len(), (SIZE.0 * SIZE.1 * 4) as usize);
+}
+
+#[test]
+fn top_corner_shows_the_sky() {
+    let pixels = render(&started_game(0));
+
+    assert_eq!(&pixels[..4], &sky_pixel()[..]);
+}
+
+#[test]
+fn cylinder_covers_part_of_the_frame() {
+    let sky = sky_pixel();
+    let pixels = render(&started_game(0));
+
+    let covered = pixels.chunks(4).filter(|pixel| *pixel != &sky[..]).count();
+
+    assert!(covered > 0);
+    assert!(covered < (SIZE.0 * SIZE.1) as usize);
+}
+
+#[test]
+fn settled_blocks_change_the_picture() {
+    let empty = started_game(0);
+    let mut filled = started_game(0);
+
+    filled.hard_drop_piece();
+
+    assert_ne!(render(&empty), render(&filled));
+}
+
+#[test]
+fn held_piece_and_preview_stay_around_the_front_column() {
+    let mut game = started_game(0);
+
+    game.hold_piece();
+
+    let sectors = |front_column| {
+        scene::moving_sectors(
+            &game,
+            &Frame {
+                front_column,
+                ..frame(&game)
+            },
+        )
+    };
+
+    let (front, turned) = (sectors(0.0), sectors(5.0));
+
+    // the current piece and its ghost come first, they stay on their columns
+    for (i, (a, b)) in front.iter().zip(&turned).enumerate() {
+        let shift = if i < 8 { 0.0 } else { 5.0 };
+
+        assert_eq!(b.column - a.column, shift, "sector {}", i);
+    }
+}
+
+#[test]
+fn projection_follows_the_frame_size() {
+    let game = started_game(0);
+
+    let project = |size, point: glm::Vec3| {
+        let frame = Frame {
+            view: glm::identity(),
+            size,
+            ..frame(&game)
+        };
+
+        let clip = frame.view_projection() * glm::vec4(point.x, point.y, point.z, 1.0);
+
+        clip.xy() / clip.w
+    };
+
+    // points at the top and at the side of the field of view
+    let edge = (DEFAULT_FOV * 0.5).tan();
+    let (top, side) = (glm::vec3(0.0, edge, -1.0), glm::vec3(edge, 0.0, -1.0));
+
+    for &size in &[(64, 64), (128, 64), (64, 128)] {
+        let aspect_ratio = size.0 as f32 / size.1 as f32;
+
+        // the height always covers the field of view, wider frames see more
+        assert!((project(size, top).y - 1.0).abs() < 1e-4);
+        assert!((project(size, side).x - 1.0 / aspect_ratio).abs() < 1e-4);
+    }
+
+    // a minimized window has no height
+    assert!(project((64, 0), side).x.is_finite());
+}
+
+#[cfg(feature = "capture")]
+#[test]
+fn capture_saves_the_frame_as_a_png() {
+    use tetris_3d::render::{self, CameraSettings};
+
+    let dir = std::env::temp_dir().join("tetris-3d-capture-test");
+    let file_path = dir.join("frame.png");
+
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let game = started_game(3);
+    let result = render::capture(&game, CameraSettings::default(), SIZE, 0.0, &file_path);
+    let image = result.and_then(|_| image::open(&file_path));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let image = image.unwrap().into_rgba8();
+
+    assert_eq!(image.dimensions(), SIZE);
+    assert_eq!(image.get_pixel(0, 0).0.to_vec(), sky_pixel());
+    assert!(image.pixels().any(|pixel| pixel.0.to_vec() != sky_pixel()));
+}
@@ -0,0 +1,56 @@
+use tetris_3d::render::scene::state_prompt;
+use tetris_3d::render::text::{self, GLYPH_HEIGHT, GLYPH_WIDTH};
+use tetris_3d::GameState;
+
+fn is_text(image: &text::TextImage, x: u32, y: u32) -> bool {
+    let index = ((y * image.width + x) * 4) as usize;
+
+    image.pixels[index..index + 4] == [255, 255, 255, 255]
+}
+
+#[test]
+fn text_sits_in_a_padded_box() {
+    let image = text::rasterize("go");
+
+    assert_eq!(image.width, 2 * GLYPH_WIDTH + 1 + 4);
+    assert_eq!(image.height, GLYPH_HEIGHT + 4);
+    assert_eq!(
+        image.pixels.len(),
+        (image.width * image.height * 4) as usize
+    );
+
+    // the box is see-through, the text is not
+    assert_eq!(image.pixels[3], 160);
+    assert!(!is_text(&image, 0, 0));
+
+    // the top row of the o, after the 2 pixels of padding
+    let top: Vec<bool> = (0..GLYPH_WIDTH)
+        .map(|x| is_text(&image, 2 + GLYPH_WIDTH + 1 + x, 2))
+        .collect();
+
+    assert_eq!(top, [false, true, true, true, false]);
+}
+
+#[test]
+fn letters_ignore_case_and_unknown_characters_are_blank() {
+    assert_eq!(text::rasterize("Enter"), text::rasterize("ENTER"));
+    assert_eq!(text::rasterize("a?b"), text::rasterize("a b"));
+
+    let blank = text::rasterize(" ");
+
+    assert!((0..blank.width).all(|x| (0..blank.height).all(|y| !is_text(&blank, x, y))));
+}
+
+#[test]
+fn frozen_states_have_a_prompt() {
+    for &state in &[GameState::Ready, GameState::Paused, GameState::GameOver] {
+        let prompt = state_prompt(state).unwrap();
+
+        assert!(prompt
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c == ' ' || c == ','));
+    }
+
+    assert_eq!(state_prompt(GameState::Playing), None);
+    assert_eq!(state_prompt(GameState::LineClearAnimating), None);
+}